@@ -30,6 +30,26 @@ fn setup_sqlx_build_db() {
     });
 }
 
+/// Exposes the commit the binary is built from in the `GIT_COMMIT` environment variable, a
+/// `GIT_COMMIT` given to the build takes precedence (e.g., when building outside of a checkout)
+fn set_git_commit() {
+    let git_commit = std::env::var("GIT_COMMIT").ok().or_else(|| {
+        std::process::Command::new("git")
+            .args(["rev-parse", "HEAD"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .and_then(|output| String::from_utf8(output.stdout).ok())
+            .map(|git_commit| git_commit.trim().to_owned())
+    });
+
+    println!(
+        "cargo:rustc-env=GIT_COMMIT={}",
+        git_commit.as_deref().unwrap_or("unknown")
+    );
+}
+
 fn main() {
     setup_sqlx_build_db();
+    set_git_commit();
 }
@@ -0,0 +1,7 @@
+CREATE TABLE UserRoles (
+    UserId TEXT NOT NULL,
+    Role TEXT NOT NULL CHECK (Role IN ("Admin", "Moderator", "Player")),
+
+    PRIMARY KEY (UserId, Role),
+    FOREIGN KEY (UserId) REFERENCES Users(Id)
+);
@@ -0,0 +1,4 @@
+INSERT INTO
+    UserRoles (UserId, Role)
+VALUES
+    ("e30ba9c8-03bf-4ae8-af35-e8366a8fe160", "Player");
@@ -0,0 +1,41 @@
+INSERT INTO
+    EntityTypes (Type)
+VALUES
+    ("Augmentation");
+
+CREATE TABLE Augmentations (
+    Id TEXT NOT NULL,
+    CharacterId TEXT NOT NULL,
+
+    Name TEXT NOT NULL,
+    Description TEXT NOT NULL,
+    Type TEXT NOT NULL CHECK (Type IN ("BioMechanical", "GeneticModification", "Mechanical")),
+
+    PRIMARY KEY (Id),
+    FOREIGN KEY (Id) REFERENCES Ids(Id),
+
+    FOREIGN KEY (CharacterId) REFERENCES Characters(Id)
+);
+
+CREATE INDEX AugmentationsCharacterIdIndex ON Augmentations (CharacterId);
+
+CREATE TRIGGER AugmentationsRejectHumansOnInsert
+BEFORE INSERT ON Augmentations
+WHEN (SELECT Race FROM Characters WHERE Id = NEW.CharacterId) = "Human"
+BEGIN
+    SELECT RAISE(ABORT, "humans cannot hold augmentations");
+END;
+
+CREATE TRIGGER AugmentationsRejectHumansOnUpdate
+BEFORE UPDATE OF CharacterId ON Augmentations
+WHEN (SELECT Race FROM Characters WHERE Id = NEW.CharacterId) = "Human"
+BEGIN
+    SELECT RAISE(ABORT, "humans cannot hold augmentations");
+END;
+
+CREATE TRIGGER CharactersRejectAugmentedHumans
+BEFORE UPDATE OF Race ON Characters
+WHEN NEW.Race = "Human" AND EXISTS (SELECT 1 FROM Augmentations WHERE CharacterId = NEW.Id)
+BEGIN
+    SELECT RAISE(ABORT, "humans cannot hold augmentations");
+END;
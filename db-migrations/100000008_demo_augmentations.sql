@@ -0,0 +1,11 @@
+INSERT INTO
+    Ids (Id, Type)
+VALUES
+    ("9c0e5b8e-6d0f-4c38-a3b4-2f8f5a8a1c01", "Augmentation"),
+    ("5b7d2f64-1a0e-4f0a-9a0c-7c1e3d9b4e02", "Augmentation");
+
+INSERT INTO
+    Augmentations (Id, CharacterId, Name, Description, Type)
+VALUES
+    ("9c0e5b8e-6d0f-4c38-a3b4-2f8f5a8a1c01", "46a23de9-1e7a-407b-9e42-3c82109c4d23", "Titanium Arm", "Hydraulic arm with a reinforced titanium frame", "Mechanical"),
+    ("5b7d2f64-1a0e-4f0a-9a0c-7c1e3d9b4e02", "46a23de9-1e7a-407b-9e42-3c82109c4d23", "Ocular Implant", "Neural-linked eye with low-light vision", "BioMechanical");
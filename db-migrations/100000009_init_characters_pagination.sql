@@ -0,0 +1 @@
+CREATE INDEX CharactersUserIdIdIndex ON Characters (UserId, Id);
@@ -0,0 +1,8 @@
+ALTER TABLE Characters ADD COLUMN CreatedAt INTEGER NOT NULL DEFAULT 0;
+
+UPDATE
+    Characters
+SET
+    CreatedAt = CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER);
+
+CREATE INDEX CharactersUserIdCreatedAtIndex ON Characters (UserId, CreatedAt);
@@ -0,0 +1,128 @@
+-- The rows of the search tables are found by the Id of their character or augmentation through
+-- these tables, their RowIds are INTEGER PRIMARY KEYs, so VACUUM keeps them, and the triggers look
+-- up the rows of the search tables by rowid instead of scanning the unindexed Id columns
+CREATE TABLE CharactersSearchRowIds (
+    RowId INTEGER PRIMARY KEY,
+    Id TEXT NOT NULL UNIQUE
+);
+
+CREATE TABLE AugmentationsSearchRowIds (
+    RowId INTEGER PRIMARY KEY,
+    Id TEXT NOT NULL UNIQUE
+);
+
+CREATE VIRTUAL TABLE CharactersSearch USING fts5 (
+    Id UNINDEXED,
+    Nickname,
+    Name,
+
+    tokenize = "unicode61 remove_diacritics 2"
+);
+
+CREATE VIRTUAL TABLE AugmentationsSearch USING fts5 (
+    Id UNINDEXED,
+    Name,
+    Description,
+
+    tokenize = "unicode61 remove_diacritics 2"
+);
+
+INSERT INTO
+    CharactersSearchRowIds (Id)
+SELECT
+    Id
+FROM
+    Characters;
+
+INSERT INTO
+    CharactersSearch (rowid, Id, Nickname, Name)
+SELECT
+    CharactersSearchRowIds.RowId, Characters.Id, Characters.Nickname, Characters.Name
+FROM
+    Characters
+        JOIN CharactersSearchRowIds ON Characters.Id = CharactersSearchRowIds.Id;
+
+INSERT INTO
+    AugmentationsSearchRowIds (Id)
+SELECT
+    Id
+FROM
+    Augmentations;
+
+INSERT INTO
+    AugmentationsSearch (rowid, Id, Name, Description)
+SELECT
+    AugmentationsSearchRowIds.RowId, Augmentations.Id, Augmentations.Name, Augmentations.Description
+FROM
+    Augmentations
+        JOIN AugmentationsSearchRowIds ON Augmentations.Id = AugmentationsSearchRowIds.Id;
+
+CREATE TRIGGER CharactersSearchOnInsert
+AFTER INSERT ON Characters
+BEGIN
+    INSERT INTO CharactersSearchRowIds (Id) VALUES (NEW.Id);
+    INSERT INTO
+        CharactersSearch (rowid, Id, Nickname, Name)
+    SELECT
+        RowId, NEW.Id, NEW.Nickname, NEW.Name
+    FROM
+        CharactersSearchRowIds
+    WHERE
+        Id = NEW.Id;
+END;
+
+CREATE TRIGGER CharactersSearchOnUpdate
+AFTER UPDATE OF Nickname, Name ON Characters
+BEGIN
+    UPDATE
+        CharactersSearch
+    SET
+        Nickname = NEW.Nickname, Name = NEW.Name
+    WHERE
+        rowid = (SELECT RowId FROM CharactersSearchRowIds WHERE Id = OLD.Id);
+END;
+
+CREATE TRIGGER CharactersSearchOnDelete
+AFTER DELETE ON Characters
+BEGIN
+    DELETE FROM
+        CharactersSearch
+    WHERE
+        rowid = (SELECT RowId FROM CharactersSearchRowIds WHERE Id = OLD.Id);
+    DELETE FROM CharactersSearchRowIds WHERE Id = OLD.Id;
+END;
+
+CREATE TRIGGER AugmentationsSearchOnInsert
+AFTER INSERT ON Augmentations
+BEGIN
+    INSERT INTO AugmentationsSearchRowIds (Id) VALUES (NEW.Id);
+    INSERT INTO
+        AugmentationsSearch (rowid, Id, Name, Description)
+    SELECT
+        RowId, NEW.Id, NEW.Name, NEW.Description
+    FROM
+        AugmentationsSearchRowIds
+    WHERE
+        Id = NEW.Id;
+END;
+
+CREATE TRIGGER AugmentationsSearchOnUpdate
+AFTER UPDATE OF Name, Description ON Augmentations
+BEGIN
+    UPDATE
+        AugmentationsSearch
+    SET
+        Name = NEW.Name, Description = NEW.Description
+    WHERE
+        rowid = (SELECT RowId FROM AugmentationsSearchRowIds WHERE Id = OLD.Id);
+END;
+
+CREATE TRIGGER AugmentationsSearchOnDelete
+AFTER DELETE ON Augmentations
+BEGIN
+    DELETE FROM
+        AugmentationsSearch
+    WHERE
+        rowid = (SELECT RowId FROM AugmentationsSearchRowIds WHERE Id = OLD.Id);
+    DELETE FROM AugmentationsSearchRowIds WHERE Id = OLD.Id;
+END;
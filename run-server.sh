@@ -1,4 +1,4 @@
 #!/bin/sh
 
-cargo run --features expose-internal-error -- serve -l localhost:8001 -p
+JWT_SECRET=development-secret cargo run --features expose-internal-error -- serve -l localhost:8001 -p
 
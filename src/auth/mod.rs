@@ -0,0 +1,221 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use async_graphql::Context;
+use axum::{
+    extract::{Request, State},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    response::Response,
+};
+use error::{InvalidToken, IssueTokenError, Unauthenticated};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::{log_location, model::scalars::id::Id};
+
+const ALGORITHM: Algorithm = Algorithm::HS256;
+
+/// Identity of the caller, placed into the request `Data` by the transport layer
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user_id: Id,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    iat: u64,
+    exp: u64,
+}
+
+struct AuthenticatorInner {
+    encoding_key: EncodingKey,
+    decoding_key: DecodingKey,
+    validation: Validation,
+}
+
+/// Issues and verifies HMAC-signed bearer tokens
+#[derive(Clone)]
+pub struct Authenticator(Arc<AuthenticatorInner>);
+
+impl Authenticator {
+    pub fn new(secret: impl AsRef<[u8]>) -> Self {
+        let secret = secret.as_ref();
+
+        Self(Arc::new(AuthenticatorInner {
+            encoding_key: EncodingKey::from_secret(secret),
+            decoding_key: DecodingKey::from_secret(secret),
+            validation: Validation::new(ALGORITHM),
+        }))
+    }
+
+    pub fn issue_token(
+        &self,
+        user_id: &Id,
+        valid_for: Duration,
+    ) -> Result<String, IssueTokenError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+
+        let claims = Claims {
+            sub: user_id.as_string_ref().clone(),
+            iat: now.as_secs(),
+            exp: (now + valid_for).as_secs(),
+        };
+
+        Ok(jsonwebtoken::encode(
+            &Header::new(ALGORITHM),
+            &claims,
+            &self.0.encoding_key,
+        )?)
+    }
+
+    pub fn verify_token(&self, token: &str) -> Result<AuthenticatedUser, InvalidToken> {
+        let token_data =
+            jsonwebtoken::decode::<Claims>(token, &self.0.decoding_key, &self.0.validation)?;
+
+        Ok(AuthenticatedUser {
+            user_id: token_data.claims.sub.into(),
+        })
+    }
+
+    pub fn verify_bearer_token(
+        &self,
+        authorization: &str,
+    ) -> Result<AuthenticatedUser, InvalidToken> {
+        let token = authorization
+            .strip_prefix("Bearer ")
+            .ok_or(InvalidToken::NotABearerToken)?;
+
+        self.verify_token(token.trim())
+    }
+
+    /// Verifies the token sent in the `connection_init` payload of a GraphQL over WebSocket
+    /// connection, the payload is expected to be in the form of `{"Authorization": "Bearer <token>"}`
+    pub fn verify_connection_init_payload(
+        &self,
+        payload: &serde_json::Value,
+    ) -> Result<AuthenticatedUser, InvalidToken> {
+        let authorization = payload
+            .as_object()
+            .and_then(|payload| {
+                payload
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(AUTHORIZATION.as_str()))
+            })
+            .and_then(|(_, value)| value.as_str())
+            .ok_or(InvalidToken::MissingToken)?;
+
+        self.verify_bearer_token(authorization)
+    }
+}
+
+/// Verifies the `Authorization` header and stores the caller in the request extensions
+///
+/// Requests without a valid token are passed through anonymously, resolvers that need an identity
+/// return [`Unauthenticated`].
+pub async fn authentication_middleware(
+    State(authenticator): State<Authenticator>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let authorization = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok());
+
+    if let Some(authorization) = authorization {
+        match authenticator.verify_bearer_token(authorization) {
+            Ok(authenticated_user) => {
+                request.extensions_mut().insert(authenticated_user);
+            }
+            Err(e) => log::debug!("{}, rejected bearer token, error = {e}", log_location!()),
+        }
+    }
+
+    next.run(request).await
+}
+
+pub trait ContextAuthExt {
+    fn authenticated_user(&self) -> Result<&AuthenticatedUser, Unauthenticated>;
+}
+
+impl ContextAuthExt for Context<'_> {
+    fn authenticated_user(&self) -> Result<&AuthenticatedUser, Unauthenticated> {
+        self.data_opt::<AuthenticatedUser>().ok_or(Unauthenticated)
+    }
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, InputType, Value};
+
+    use crate::{error::ErrorCode, model::enums::role::Role};
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("Unauthenticated: the request does not carry a valid bearer token")]
+    pub struct Unauthenticated;
+
+    impl ErrorCode for Unauthenticated {
+        fn code(&self) -> &'static str {
+            "UNAUTHENTICATED"
+        }
+    }
+
+    impl ErrorExtensions for Unauthenticated {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("Forbidden: required_role = '{required_role}'")]
+    pub struct Forbidden {
+        pub required_role: Role,
+    }
+
+    impl ErrorCode for Forbidden {
+        fn code(&self) -> &'static str {
+            "FORBIDDEN"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![("requiredRole", self.required_role.to_value())]
+        }
+    }
+
+    impl ErrorExtensions for Forbidden {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum InvalidToken {
+        #[error("InvalidToken: no token was provided")]
+        MissingToken,
+
+        #[error("InvalidToken: authorization header is not a bearer token")]
+        NotABearerToken,
+
+        #[error("InvalidToken: '{0}'")]
+        JwtError(
+            #[from]
+            #[source]
+            jsonwebtoken::errors::Error,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum IssueTokenError {
+        #[error("IssueTokenError: '{0}'")]
+        JwtError(
+            #[from]
+            #[source]
+            jsonwebtoken::errors::Error,
+        ),
+    }
+}
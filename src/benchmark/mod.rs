@@ -0,0 +1,247 @@
+use std::{
+    fs::remove_dir_all,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use async_graphql::{Schema, Variables};
+use error::BenchmarkError;
+use serde_json::json;
+
+use crate::{
+    auth::AuthenticatedUser,
+    cli::BenchCharactersParams,
+    config::LimitsConfig,
+    create_schema,
+    database::DatabaseOptions,
+    model::{
+        enums::character_race::CharacterRace,
+        inputs::character_creation_input::CharacterCreationInput, mutation::Mutation, query::Query,
+        subscription::Subscription,
+    },
+    sql_queries::sql_character::create_character,
+    state::State,
+};
+
+/// Log target of the statements executed by sqlx
+const SQLX_QUERY_LOG_TARGET: &str = "sqlx::query";
+
+/// User of the demo data, the benchmark characters are created for this user
+const DEMO_USER_ID: &str = "e30ba9c8-03bf-4ae8-af35-e8366a8fe160";
+
+const CHARACTERS_QUERY: &str = "query Characters($after: String) { me { characters(first: 100, after: $after) { totalCount pageInfo { hasNextPage endCursor } nodes { id name nickname race } } } }";
+
+static EXECUTED_QUERY_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts the statements executed by sqlx, every statement is logged under the
+/// [`SQLX_QUERY_LOG_TARGET`] target
+struct QueryCountingLogger;
+
+impl log::Log for QueryCountingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == SQLX_QUERY_LOG_TARGET
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            EXECUTED_QUERY_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static QUERY_COUNTING_LOGGER: QueryCountingLogger = QueryCountingLogger;
+
+/// Measures the number of executed queries of the pages and the duration of loading every page of
+/// the `characters` connection for different numbers of characters, fails if the number of queries
+/// of a page depends on the number of characters
+///
+/// The pages after a cursor also check whether there are rows before the cursor, so they are
+/// compared to each other and not to the first page.
+///
+/// Installs its own logger, so it has to be called instead of initializing the regular one.
+pub async fn bench_characters(params: &BenchCharactersParams) -> Result<(), BenchmarkError> {
+    log::set_logger(&QUERY_COUNTING_LOGGER)?;
+    log::set_max_level(log::LevelFilter::Debug);
+
+    println!(
+        "characters\tpages\tqueries of the first page\tqueries of the next pages\taverage duration"
+    );
+
+    // the first measurement of the first page and of the next pages, with its number of characters
+    let mut first_measurements: [Option<(usize, usize)>; 2] = [None, None];
+
+    for &character_count in &params.character_counts {
+        let db_folder_path =
+            std::env::temp_dir().join(format!("graphql-example-bench-{}", uuid::Uuid::new_v4()));
+
+        let result = measure_characters(&db_folder_path, character_count, params.iterations).await;
+        let _ = remove_dir_all(&db_folder_path);
+        let (page_query_counts, average_duration) = result?;
+
+        let format_query_count =
+            |query_count: Option<&usize>| query_count.map_or("-".into(), usize::to_string);
+        println!(
+            "{character_count}\t{}\t{}\t{}\t{average_duration:?}",
+            page_query_counts.len(),
+            format_query_count(page_query_counts.first()),
+            format_query_count(page_query_counts.get(1)),
+        );
+
+        for (page_index, query_count) in page_query_counts.into_iter().enumerate() {
+            match &mut first_measurements[page_index.min(1)] {
+                first_measurement @ None => {
+                    *first_measurement = Some((character_count, query_count))
+                }
+                Some((first_character_count, first_query_count))
+                    if *first_query_count != query_count =>
+                {
+                    return Err(BenchmarkError::QueryCountChanged {
+                        first_character_count: *first_character_count,
+                        first_query_count: *first_query_count,
+                        character_count,
+                        query_count,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the number of queries of each page of the last iteration and the average duration of
+/// loading every page
+async fn measure_characters(
+    db_folder_path: &Path,
+    character_count: usize,
+    iterations: u32,
+) -> Result<(Vec<usize>, Duration), BenchmarkError> {
+    const RACES: [CharacterRace; 3] = [
+        CharacterRace::Android,
+        CharacterRace::Cyborg,
+        CharacterRace::Human,
+    ];
+
+    let state = State::new(&DatabaseOptions::in_folder(db_folder_path)).await?;
+    let user_id = DEMO_USER_ID.to_string().into();
+
+    for index in 0..character_count {
+        let character_definition = CharacterCreationInput {
+            race: RACES[index % RACES.len()],
+            nickname: format!("bench-{index}"),
+            name: None,
+        };
+        create_character(&state, &user_id, &character_definition).await?;
+    }
+
+    let schema = create_schema(state.clone(), &LimitsConfig::default());
+    let user = AuthenticatedUser { user_id };
+
+    // warm-up, so that opening the pooled connections is not measured
+    load_every_page(&schema, &user).await?;
+
+    let mut page_query_counts = Vec::new();
+    let mut total_duration = Duration::ZERO;
+
+    for _ in 0..iterations.max(1) {
+        let started_at = Instant::now();
+        page_query_counts = load_every_page(&schema, &user).await?;
+        total_duration += started_at.elapsed();
+    }
+
+    state.database.connection_pool_ref().close().await;
+
+    Ok((page_query_counts, total_duration / iterations.max(1)))
+}
+
+/// Follows the `endCursor` of the pages until the last page, returns the number of queries
+/// executed for each page
+async fn load_every_page(
+    schema: &Schema<Query, Mutation, Subscription>,
+    user: &AuthenticatedUser,
+) -> Result<Vec<usize>, BenchmarkError> {
+    let mut page_query_counts = Vec::new();
+    let mut after = serde_json::Value::Null;
+
+    loop {
+        EXECUTED_QUERY_COUNT.store(0, Ordering::Relaxed);
+
+        let response = schema
+            .execute(
+                async_graphql::Request::new(CHARACTERS_QUERY)
+                    .variables(Variables::from_json(json!({ "after": after })))
+                    .data(user.clone()),
+            )
+            .await;
+
+        page_query_counts.push(EXECUTED_QUERY_COUNT.load(Ordering::Relaxed));
+
+        if response.is_err() {
+            return Err(BenchmarkError::RequestFailed(response.errors));
+        }
+
+        let data = response
+            .data
+            .into_json()
+            .map_err(|e| BenchmarkError::UnexpectedResponse(e.to_string()))?;
+        let page_info = &data["me"]["characters"]["pageInfo"];
+
+        match (&page_info["hasNextPage"], &page_info["endCursor"]) {
+            (serde_json::Value::Bool(true), end_cursor @ serde_json::Value::String(_)) => {
+                after = end_cursor.clone();
+            }
+            (serde_json::Value::Bool(false), _) => return Ok(page_query_counts),
+            _ => return Err(BenchmarkError::UnexpectedResponse(page_info.to_string())),
+        }
+    }
+}
+
+pub mod error {
+    use crate::{
+        error::DatabaseOpenError, sql_queries::sql_character::error::CreateCharacterError,
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum BenchmarkError {
+        #[error("BenchmarkError: '{0}'")]
+        SetLoggerError(
+            #[from]
+            #[source]
+            log::SetLoggerError,
+        ),
+
+        #[error("BenchmarkError: '{0}'")]
+        DatabaseOpenError(
+            #[from]
+            #[source]
+            DatabaseOpenError,
+        ),
+
+        #[error("BenchmarkError: '{0}'")]
+        CreateCharacterError(
+            #[from]
+            #[source]
+            CreateCharacterError,
+        ),
+
+        #[error("BenchmarkError: request failed, errors = '{0:?}'")]
+        RequestFailed(Vec<async_graphql::ServerError>),
+
+        #[error("BenchmarkError: unexpected response, '{0}'")]
+        UnexpectedResponse(String),
+
+        #[error(
+            "BenchmarkError: the number of queries of a page depends on the number of characters, {first_query_count} queries for {first_character_count} characters, {query_count} queries for {character_count} characters"
+        )]
+        QueryCountChanged {
+            first_character_count: usize,
+            first_query_count: usize,
+            character_count: usize,
+            query_count: usize,
+        },
+    }
+}
@@ -1,9 +1,18 @@
 use clap::Parser;
 
-use std::{path::PathBuf, str::FromStr};
+use std::{
+    io::{IsTerminal, Write},
+    path::PathBuf,
+    str::FromStr,
+};
 
 use error::CannotParseSchemaSource;
 
+use crate::{
+    config::{JournalMode, Synchronous},
+    model::{enums::role::Role, scalars::user_id::UserId},
+};
+
 #[derive(Debug, Clone)]
 pub enum SchemaSource {
     File(PathBuf),
@@ -24,14 +33,200 @@ impl FromStr for SchemaSource {
     }
 }
 
+/// Values of the configuration given on the command line or in environment variables, they
+/// override the values of the configuration file
 #[derive(Debug, Parser)]
-pub struct ServeParams {
+pub struct ConfigParams {
     #[arg(
+        global(true),
+        long("config"),
+        env("CONFIG_FILE"),
+        help("TOML file of the configuration")
+    )]
+    pub config_path: Option<PathBuf>,
+
+    #[arg(
+        global(true),
         short('l'),
         long("listener-address"),
+        env("LISTENER_ADDRESS"),
         help("Address where the server accepts the connections (e.g., 127.0.0.1:8000)")
     )]
-    pub listener_address: String,
+    pub listener_address: Option<String>,
+
+    #[arg(
+        global(true),
+        long("shutdown-timeout-secs"),
+        env("SHUTDOWN_TIMEOUT_SECS"),
+        help("Number of seconds the shutdown waits for the in-flight work [default: 30]")
+    )]
+    pub shutdown_timeout_secs: Option<u64>,
+
+    #[arg(
+        global(true),
+        long("db-path"),
+        env("DB_PATH"),
+        help("Folder of the database [default: db]")
+    )]
+    pub db_path: Option<PathBuf>,
+
+    #[arg(
+        global(true),
+        long("db-in-memory"),
+        env("DB_IN_MEMORY"),
+        conflicts_with("db_path"),
+        help("Keeps the database in memory, the data is lost when the process exits")
+    )]
+    pub db_in_memory: bool,
+
+    #[arg(
+        global(true),
+        long("db-max-connections"),
+        env("DB_MAX_CONNECTIONS"),
+        help("Maximum number of connections in the connection pool [default: 10]")
+    )]
+    pub db_max_connections: Option<u32>,
+
+    #[arg(
+        global(true),
+        long("db-busy-timeout-ms"),
+        env("DB_BUSY_TIMEOUT_MS"),
+        help("Milliseconds a connection waits for a locked database [default: 5000]")
+    )]
+    pub db_busy_timeout_ms: Option<u64>,
+
+    #[arg(
+        global(true),
+        long("db-journal-mode"),
+        env("DB_JOURNAL_MODE"),
+        help("Journal mode of the database [default: wal]")
+    )]
+    pub db_journal_mode: Option<JournalMode>,
+
+    #[arg(
+        global(true),
+        long("db-synchronous"),
+        env("DB_SYNCHRONOUS"),
+        help("Synchronous level of the database [default: full]")
+    )]
+    pub db_synchronous: Option<Synchronous>,
+
+    #[arg(
+        global(true),
+        long("log-filter"),
+        env("LOG_FILTER"),
+        help("Comma separated '[module=]level' log directives")
+    )]
+    pub log_filter: Option<String>,
+
+    #[arg(
+        global(true),
+        long("cors-allowed-origins"),
+        env("CORS_ALLOWED_ORIGINS"),
+        value_delimiter(','),
+        help(
+            "Comma separated origins that may call the API, '*.' matches the subdomains (e.g., https://*.example.com) and '*' allows every origin [default: *]"
+        )
+    )]
+    pub cors_allowed_origins: Option<Vec<String>>,
+
+    #[arg(
+        global(true),
+        long("cors-allowed-methods"),
+        env("CORS_ALLOWED_METHODS"),
+        value_delimiter(','),
+        help("Comma separated methods allowed for cross-origin requests [default: *]")
+    )]
+    pub cors_allowed_methods: Option<Vec<String>>,
+
+    #[arg(
+        global(true),
+        long("cors-allowed-headers"),
+        env("CORS_ALLOWED_HEADERS"),
+        value_delimiter(','),
+        help("Comma separated request headers allowed for cross-origin requests [default: *]")
+    )]
+    pub cors_allowed_headers: Option<Vec<String>>,
+
+    #[arg(
+        global(true),
+        long("cors-exposed-headers"),
+        env("CORS_EXPOSED_HEADERS"),
+        value_delimiter(','),
+        help("Comma separated response headers exposed to cross-origin scripts")
+    )]
+    pub cors_exposed_headers: Option<Vec<String>>,
+
+    #[arg(
+        global(true),
+        long("cors-allow-credentials"),
+        env("CORS_ALLOW_CREDENTIALS"),
+        help("Whether cross-origin requests may carry credentials [default: false]")
+    )]
+    pub cors_allow_credentials: Option<bool>,
+
+    #[arg(
+        global(true),
+        long("cors-max-age-secs"),
+        env("CORS_MAX_AGE_SECS"),
+        help("Number of seconds the browsers may cache the preflight responses for")
+    )]
+    pub cors_max_age_secs: Option<u64>,
+
+    #[arg(
+        global(true),
+        long("jwt-secret"),
+        env("JWT_SECRET"),
+        hide_env_values(true),
+        help("Secret used for signing and verifying the bearer tokens")
+    )]
+    pub jwt_secret: Option<String>,
+
+    #[arg(
+        global(true),
+        long("token-valid-for-secs"),
+        env("TOKEN_VALID_FOR_SECS"),
+        help("Number of seconds the issued tokens are valid for [default: 86400]")
+    )]
+    pub token_valid_for_secs: Option<u64>,
+
+    #[arg(
+        global(true),
+        long("max-request-body-bytes"),
+        env("MAX_REQUEST_BODY_BYTES"),
+        help("Largest accepted request body [default: 2097152]")
+    )]
+    pub max_request_body_bytes: Option<usize>,
+
+    #[arg(
+        global(true),
+        long("max-query-depth"),
+        env("MAX_QUERY_DEPTH"),
+        help("Deepest accepted nesting of a query, unlimited when not given")
+    )]
+    pub max_query_depth: Option<usize>,
+
+    #[arg(
+        global(true),
+        long("max-query-complexity"),
+        env("MAX_QUERY_COMPLEXITY"),
+        help("Highest accepted complexity of a query, unlimited when not given")
+    )]
+    pub max_query_complexity: Option<usize>,
+}
+
+#[derive(Debug, Parser)]
+pub struct IssueTokenParams {
+    #[arg(help("Id of the user the token is issued for"))]
+    pub user_id: UserId,
+}
+
+#[derive(Debug, Parser)]
+pub struct GrantRoleParams {
+    #[arg(help("Id of the user the role is granted to"))]
+    pub user_id: UserId,
+    #[arg(help("Format: 'Player|Moderator|Admin'"))]
+    pub role: Role,
 }
 
 #[derive(Debug, Parser)]
@@ -42,11 +237,43 @@ pub struct DiffSchemaParams {
     pub schema_source_right: SchemaSource,
 }
 
+#[derive(Debug, Parser)]
+pub struct BenchCharactersParams {
+    #[arg(
+        long("character-counts"),
+        value_delimiter(','),
+        default_values_t = [1, 10, 100, 1000],
+        help("Comma separated numbers of characters to measure with")
+    )]
+    pub character_counts: Vec<usize>,
+
+    #[arg(
+        long("iterations"),
+        default_value_t = 10,
+        help("Number of measured requests for each number of characters")
+    )]
+    pub iterations: u32,
+}
+
+#[derive(Debug, Parser)]
+pub enum ConfigCommands {
+    /// Prints the effective configuration and the validation errors
+    Check,
+}
+
 #[derive(Debug, Parser)]
 pub enum Commands {
-    Serve(ServeParams),
+    Serve,
+    IssueToken(IssueTokenParams),
+    /// Grants a role to a user, e.g., to create the first admin
+    GrantRole(GrantRoleParams),
     Sdl,
     DiffSchema(DiffSchemaParams),
+    /// Measures the number of queries and the duration of loading the characters of a user
+    BenchCharacters(BenchCharactersParams),
+    /// Inspects the configuration
+    #[command(subcommand)]
+    Config(ConfigCommands),
 }
 
 #[derive(Debug, Parser)]
@@ -58,9 +285,43 @@ pub struct Cli {
         global(true),
         short('p'),
         long("purge-db"),
-        help("Purges everything from the database at start")
+        help("Deletes the database files at start, after asking for confirmation")
     )]
     pub purge_db: bool,
+
+    #[arg(
+        global(true),
+        short('y'),
+        long("yes"),
+        help("Answers yes to the confirmation questions")
+    )]
+    pub yes: bool,
+
+    #[command(flatten)]
+    pub config_params: ConfigParams,
+}
+
+/// Asks a yes or no question on the terminal, anything but `y` or `yes` is taken as no
+///
+/// Fails when the standard input is not a terminal, so the answer cannot be given by accident.
+pub fn ask_confirmation(question: &str) -> std::io::Result<bool> {
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        return Err(std::io::Error::other(
+            "cannot ask for confirmation, the standard input is not a terminal",
+        ));
+    }
+
+    print!("{question} [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    stdin.read_line(&mut answer)?;
+
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
 }
 
 pub mod error {
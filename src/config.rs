@@ -0,0 +1,575 @@
+use std::{
+    fs::read_to_string,
+    net::ToSocketAddrs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use axum::http::{HeaderName, Method};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteJournalMode, SqliteSynchronous};
+
+use error::{ConfigValidationError, InvalidConfig, LoadConfigError};
+
+use crate::{
+    cli::ConfigParams,
+    cors::OriginPattern,
+    database::{DatabaseLocation, DatabaseOptions},
+};
+
+/// Log filter used when none is configured, in the syntax of `env_logger` (e.g., `info,sqlx=warn`)
+pub const DEFAULT_LOG_FILTER: &str = "graphql_example=trace,tower_http=debug";
+
+/// Value of the CORS lists that allows everything
+pub const CORS_WILDCARD: &str = "*";
+
+/// Printed instead of the secrets of the configuration
+const REDACTED_VALUE: &str = "<redacted>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+    Off,
+}
+
+impl From<JournalMode> for SqliteJournalMode {
+    fn from(journal_mode: JournalMode) -> Self {
+        match journal_mode {
+            JournalMode::Delete => SqliteJournalMode::Delete,
+            JournalMode::Truncate => SqliteJournalMode::Truncate,
+            JournalMode::Persist => SqliteJournalMode::Persist,
+            JournalMode::Memory => SqliteJournalMode::Memory,
+            JournalMode::Wal => SqliteJournalMode::Wal,
+            JournalMode::Off => SqliteJournalMode::Off,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+impl From<Synchronous> for SqliteSynchronous {
+    fn from(synchronous: Synchronous) -> Self {
+        match synchronous {
+            Synchronous::Off => SqliteSynchronous::Off,
+            Synchronous::Normal => SqliteSynchronous::Normal,
+            Synchronous::Full => SqliteSynchronous::Full,
+            Synchronous::Extra => SqliteSynchronous::Extra,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// Address where the server accepts the connections (e.g., 127.0.0.1:8000)
+    pub listener_address: Option<String>,
+    /// Number of seconds the shutdown waits for the in-flight requests and the WebSocket
+    /// connections before dropping them
+    pub shutdown_timeout_secs: u64,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            listener_address: None,
+            shutdown_timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    pub path: PathBuf,
+    pub in_memory: bool,
+    pub max_connections: u32,
+    pub busy_timeout_ms: u64,
+    pub journal_mode: JournalMode,
+    pub synchronous: Synchronous,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: "db".into(),
+            in_memory: false,
+            max_connections: 10,
+            busy_timeout_ms: 5000,
+            journal_mode: JournalMode::Wal,
+            synchronous: Synchronous::Full,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// Comma separated `[module=]level` directives
+    pub filter: String,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            filter: DEFAULT_LOG_FILTER.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Origins that may call the API (e.g., `https://example.com` or `https://*.example.com`),
+    /// `*` allows every origin
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed in the preflight response, `*` allows every method
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed in the preflight response, `*` allows every header
+    pub allowed_headers: Vec<String>,
+    /// Response headers the browser exposes to the scripts, `*` exposes every header
+    pub exposed_headers: Vec<String>,
+    /// Whether the browser may send cookies and the `Authorization` header, cannot be used
+    /// together with `*` origins or exposed headers
+    pub allow_credentials: bool,
+    /// Number of seconds the browser may cache the preflight response for
+    pub max_age_secs: Option<u64>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec![CORS_WILDCARD.into()],
+            allowed_methods: vec![CORS_WILDCARD.into()],
+            allowed_headers: vec![CORS_WILDCARD.into()],
+            exposed_headers: Vec::new(),
+            allow_credentials: false,
+            max_age_secs: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthConfig {
+    /// Secret used for signing and verifying the bearer tokens
+    pub jwt_secret: Option<String>,
+    /// Number of seconds the issued tokens are valid for
+    pub token_valid_for_secs: u64,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            jwt_secret: None,
+            token_valid_for_secs: 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsConfig {
+    /// Largest accepted request body
+    pub max_request_body_bytes: usize,
+    /// Deepest accepted selection set nesting of a query, unlimited when not set
+    pub max_query_depth: Option<usize>,
+    /// Highest accepted complexity of a query, unlimited when not set
+    pub max_query_complexity: Option<usize>,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_request_body_bytes: 2 * 1024 * 1024,
+            max_query_depth: None,
+            max_query_complexity: None,
+        }
+    }
+}
+
+/// Configuration of the binary
+///
+/// The values are taken from the command line first, then from the environment variables, then
+/// from the configuration file, and the defaults are used for the rest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub logging: LoggingConfig,
+    pub cors: CorsConfig,
+    pub auth: AuthConfig,
+    pub limits: LimitsConfig,
+}
+
+impl Config {
+    /// Reads the configuration file given in the parameters, then overrides its values with the
+    /// ones given on the command line or in environment variables
+    pub fn load(params: &ConfigParams) -> Result<Self, LoadConfigError> {
+        let mut config = match &params.config_path {
+            Some(config_path) => Self::from_file(config_path)?,
+            None => Self::default(),
+        };
+
+        config.apply_params(params);
+
+        Ok(config)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, LoadConfigError> {
+        let content = read_to_string(path).map_err(|source| LoadConfigError::CannotReadFile {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        toml::from_str(&content).map_err(|source| LoadConfigError::CannotParseFile {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    fn apply_params(&mut self, params: &ConfigParams) {
+        if let Some(listener_address) = &params.listener_address {
+            self.server.listener_address = Some(listener_address.clone());
+        }
+        if let Some(shutdown_timeout_secs) = params.shutdown_timeout_secs {
+            self.server.shutdown_timeout_secs = shutdown_timeout_secs;
+        }
+
+        if let Some(db_path) = &params.db_path {
+            self.database.path = db_path.clone();
+            self.database.in_memory = false;
+        }
+        if params.db_in_memory {
+            self.database.in_memory = true;
+        }
+        if let Some(db_max_connections) = params.db_max_connections {
+            self.database.max_connections = db_max_connections;
+        }
+        if let Some(db_busy_timeout_ms) = params.db_busy_timeout_ms {
+            self.database.busy_timeout_ms = db_busy_timeout_ms;
+        }
+        if let Some(db_journal_mode) = params.db_journal_mode {
+            self.database.journal_mode = db_journal_mode;
+        }
+        if let Some(db_synchronous) = params.db_synchronous {
+            self.database.synchronous = db_synchronous;
+        }
+
+        if let Some(log_filter) = &params.log_filter {
+            self.logging.filter = log_filter.clone();
+        }
+
+        if let Some(cors_allowed_origins) = &params.cors_allowed_origins {
+            self.cors.allowed_origins = cors_allowed_origins.clone();
+        }
+        if let Some(cors_allowed_methods) = &params.cors_allowed_methods {
+            self.cors.allowed_methods = cors_allowed_methods.clone();
+        }
+        if let Some(cors_allowed_headers) = &params.cors_allowed_headers {
+            self.cors.allowed_headers = cors_allowed_headers.clone();
+        }
+        if let Some(cors_exposed_headers) = &params.cors_exposed_headers {
+            self.cors.exposed_headers = cors_exposed_headers.clone();
+        }
+        if let Some(cors_allow_credentials) = params.cors_allow_credentials {
+            self.cors.allow_credentials = cors_allow_credentials;
+        }
+        if let Some(cors_max_age_secs) = params.cors_max_age_secs {
+            self.cors.max_age_secs = Some(cors_max_age_secs);
+        }
+
+        if let Some(jwt_secret) = &params.jwt_secret {
+            self.auth.jwt_secret = Some(jwt_secret.clone());
+        }
+        if let Some(token_valid_for_secs) = params.token_valid_for_secs {
+            self.auth.token_valid_for_secs = token_valid_for_secs;
+        }
+
+        if let Some(max_request_body_bytes) = params.max_request_body_bytes {
+            self.limits.max_request_body_bytes = max_request_body_bytes;
+        }
+        if let Some(max_query_depth) = params.max_query_depth {
+            self.limits.max_query_depth = Some(max_query_depth);
+        }
+        if let Some(max_query_complexity) = params.max_query_complexity {
+            self.limits.max_query_complexity = Some(max_query_complexity);
+        }
+    }
+
+    /// Checks the values of the configuration, every invalid value is reported
+    pub fn validate(&self) -> Result<(), InvalidConfig> {
+        InvalidConfig::from_errors(self.validation_errors())
+    }
+
+    /// Checks the values of the configuration and that everything the server needs is given
+    pub fn validate_for_serving(&self) -> Result<(), InvalidConfig> {
+        let mut errors = self.validation_errors();
+        errors.extend(self.listener_address().err());
+        errors.extend(self.jwt_secret().err());
+
+        InvalidConfig::from_errors(errors)
+    }
+
+    fn validation_errors(&self) -> Vec<ConfigValidationError> {
+        let mut errors = Vec::new();
+
+        if let Some(listener_address) = &self.server.listener_address {
+            if let Err(e) = listener_address.to_socket_addrs() {
+                errors.push(ConfigValidationError::invalid(
+                    "server.listener_address",
+                    listener_address,
+                    e,
+                ));
+            }
+        }
+
+        if self.database.max_connections == 0 {
+            errors.push(ConfigValidationError::invalid(
+                "database.max_connections",
+                self.database.max_connections,
+                "has to be at least 1",
+            ));
+        }
+
+        // a directive without `=` is either a level or a module name, so only `module=level`
+        // directives can be checked
+        for directive in self.logging.filter.split(',').map(str::trim) {
+            let Some((_module, level)) = directive.split_once('=') else {
+                continue;
+            };
+
+            if log::LevelFilter::from_str(level.trim()).is_err() {
+                errors.push(ConfigValidationError::invalid(
+                    "logging.filter",
+                    directive,
+                    "unknown log level, expected off, error, warn, info, debug or trace",
+                ));
+            }
+        }
+
+        for origin in &self.cors.allowed_origins {
+            match OriginPattern::from_str(origin) {
+                Ok(OriginPattern::Any) if self.cors.allow_credentials => {
+                    errors.push(ConfigValidationError::invalid(
+                        "cors.allowed_origins",
+                        origin,
+                        "cannot be used together with cors.allow_credentials",
+                    ));
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    errors.push(ConfigValidationError::invalid(
+                        "cors.allowed_origins",
+                        origin,
+                        e,
+                    ));
+                }
+            }
+        }
+        for method in &self.cors.allowed_methods {
+            if method != CORS_WILDCARD {
+                if let Err(e) = Method::from_str(method) {
+                    errors.push(ConfigValidationError::invalid(
+                        "cors.allowed_methods",
+                        method,
+                        e,
+                    ));
+                }
+            }
+        }
+        for header in &self.cors.allowed_headers {
+            if header != CORS_WILDCARD {
+                if let Err(e) = HeaderName::from_str(header) {
+                    errors.push(ConfigValidationError::invalid(
+                        "cors.allowed_headers",
+                        header,
+                        e,
+                    ));
+                }
+            }
+        }
+        for header in &self.cors.exposed_headers {
+            if header == CORS_WILDCARD {
+                if self.cors.allow_credentials {
+                    errors.push(ConfigValidationError::invalid(
+                        "cors.exposed_headers",
+                        header,
+                        "cannot be used together with cors.allow_credentials",
+                    ));
+                }
+            } else if let Err(e) = HeaderName::from_str(header) {
+                errors.push(ConfigValidationError::invalid(
+                    "cors.exposed_headers",
+                    header,
+                    e,
+                ));
+            }
+        }
+
+        if self.auth.jwt_secret.as_ref().is_some_and(String::is_empty) {
+            errors.push(ConfigValidationError::invalid(
+                "auth.jwt_secret",
+                "",
+                "cannot be empty",
+            ));
+        }
+        if self.auth.token_valid_for_secs == 0 {
+            errors.push(ConfigValidationError::invalid(
+                "auth.token_valid_for_secs",
+                self.auth.token_valid_for_secs,
+                "has to be at least 1",
+            ));
+        }
+
+        if self.limits.max_request_body_bytes == 0 {
+            errors.push(ConfigValidationError::invalid(
+                "limits.max_request_body_bytes",
+                self.limits.max_request_body_bytes,
+                "has to be at least 1",
+            ));
+        }
+        if self.limits.max_query_depth == Some(0) {
+            errors.push(ConfigValidationError::invalid(
+                "limits.max_query_depth",
+                0,
+                "has to be at least 1",
+            ));
+        }
+        if self.limits.max_query_complexity == Some(0) {
+            errors.push(ConfigValidationError::invalid(
+                "limits.max_query_complexity",
+                0,
+                "has to be at least 1",
+            ));
+        }
+
+        errors
+    }
+
+    pub fn listener_address(&self) -> Result<&str, ConfigValidationError> {
+        self.server
+            .listener_address
+            .as_deref()
+            .ok_or(ConfigValidationError::MissingValue {
+                key: "server.listener_address",
+            })
+    }
+
+    pub fn jwt_secret(&self) -> Result<&str, ConfigValidationError> {
+        self.auth
+            .jwt_secret
+            .as_deref()
+            .ok_or(ConfigValidationError::MissingValue {
+                key: "auth.jwt_secret",
+            })
+    }
+
+    pub fn shutdown_timeout(&self) -> Duration {
+        Duration::from_secs(self.server.shutdown_timeout_secs)
+    }
+
+    pub fn token_valid_for(&self) -> Duration {
+        Duration::from_secs(self.auth.token_valid_for_secs)
+    }
+
+    pub fn database_options(&self) -> DatabaseOptions {
+        DatabaseOptions {
+            location: if self.database.in_memory {
+                DatabaseLocation::InMemory
+            } else {
+                DatabaseLocation::Folder(self.database.path.clone())
+            },
+            max_connections: self.database.max_connections,
+            busy_timeout: Duration::from_millis(self.database.busy_timeout_ms),
+            journal_mode: self.database.journal_mode.into(),
+            synchronous: self.database.synchronous.into(),
+        }
+    }
+
+    /// Serializes the configuration into TOML, the secrets are replaced with a placeholder
+    pub fn to_redacted_toml(&self) -> Result<String, toml::ser::Error> {
+        let mut config = self.clone();
+        if config.auth.jwt_secret.is_some() {
+            config.auth.jwt_secret = Some(REDACTED_VALUE.into());
+        }
+
+        toml::to_string_pretty(&config)
+    }
+}
+
+pub mod error {
+    use std::{fmt::Display, path::PathBuf};
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum LoadConfigError {
+        #[error("LoadConfigError: cannot read file = '{path:?}', error = '{source}'")]
+        CannotReadFile {
+            path: PathBuf,
+            #[source]
+            source: std::io::Error,
+        },
+
+        #[error("LoadConfigError: cannot parse file = '{path:?}', error = '{source}'")]
+        CannotParseFile {
+            path: PathBuf,
+            #[source]
+            source: toml::de::Error,
+        },
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum ConfigValidationError {
+        #[error("ConfigValidationError: no value is given, key = '{key}'")]
+        MissingValue { key: &'static str },
+
+        #[error("ConfigValidationError: key = '{key}', value = '{value}', reason = '{reason}'")]
+        InvalidValue {
+            key: &'static str,
+            value: String,
+            reason: String,
+        },
+    }
+
+    impl ConfigValidationError {
+        pub fn invalid(key: &'static str, value: impl Display, reason: impl Display) -> Self {
+            Self::InvalidValue {
+                key,
+                value: value.to_string(),
+                reason: reason.to_string(),
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error(
+        "InvalidConfig: errors = '[{}]'",
+        .errors.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    pub struct InvalidConfig {
+        pub errors: Vec<ConfigValidationError>,
+    }
+
+    impl InvalidConfig {
+        pub fn from_errors(errors: Vec<ConfigValidationError>) -> Result<(), Self> {
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(Self { errors })
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use error::InvalidCorsPolicy;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
+
+use crate::config::{CorsConfig, CORS_WILDCARD};
+
+/// Origin or group of origins allowed to call the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    /// `*`, every origin
+    Any,
+    /// `scheme://host[:port]`, compared case-insensitively
+    Exact(String),
+    /// `scheme://*.domain[:port]`, every subdomain of the domain at any depth, the domain itself
+    /// is not included
+    Subdomains { scheme: String, suffix: String },
+}
+
+impl OriginPattern {
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+
+        match self {
+            Self::Any => true,
+            Self::Exact(allowed_origin) => origin == *allowed_origin,
+            Self::Subdomains { scheme, suffix } => origin
+                .strip_prefix(scheme.as_str())
+                .and_then(|origin| origin.strip_suffix(suffix.as_str()))
+                .is_some_and(|subdomain| {
+                    !subdomain.is_empty()
+                        && !subdomain.starts_with('.')
+                        && subdomain
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                }),
+        }
+    }
+}
+
+impl FromStr for OriginPattern {
+    type Err = InvalidCorsPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == CORS_WILDCARD {
+            return Ok(Self::Any);
+        }
+
+        let invalid_origin = || InvalidCorsPolicy::InvalidOrigin(s.into());
+
+        let pattern = s.to_ascii_lowercase();
+        let (scheme, host_and_port) = ["http://", "https://"]
+            .into_iter()
+            .find_map(|scheme| {
+                pattern
+                    .strip_prefix(scheme)
+                    .map(|host_and_port| (scheme, host_and_port))
+            })
+            .ok_or_else(invalid_origin)?;
+
+        let host = match host_and_port.rsplit_once(':') {
+            Some((host, port)) if port.parse::<u16>().is_ok() => host,
+            Some(_) => return Err(invalid_origin()),
+            None => host_and_port,
+        };
+
+        let is_valid_host = |host: &str| {
+            !host.is_empty()
+                && host.split('.').all(|label| {
+                    !label.is_empty()
+                        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                })
+        };
+
+        match host.strip_prefix("*.") {
+            Some(domain) if is_valid_host(domain) => Ok(Self::Subdomains {
+                scheme: scheme.into(),
+                suffix: host_and_port
+                    .strip_prefix('*')
+                    .ok_or_else(invalid_origin)?
+                    .into(),
+            }),
+            None if is_valid_host(host) => Ok(Self::Exact(pattern)),
+            _ => Err(invalid_origin()),
+        }
+    }
+}
+
+struct CorsPolicyInner {
+    origin_patterns: Vec<OriginPattern>,
+    allowed_methods: Vec<Method>,
+    allowed_headers: Vec<HeaderName>,
+    exposed_headers: Vec<HeaderName>,
+    allows_any_method: bool,
+    allows_any_header: bool,
+    exposes_any_header: bool,
+    allow_credentials: bool,
+    max_age: Option<Duration>,
+}
+
+/// Decides which origins may call the API, shared by the HTTP and the WebSocket endpoints
+#[derive(Clone)]
+pub struct CorsPolicy(Arc<CorsPolicyInner>);
+
+impl CorsPolicy {
+    pub fn new(config: &CorsConfig) -> Result<Self, InvalidCorsPolicy> {
+        let origin_patterns = config
+            .allowed_origins
+            .iter()
+            .map(|origin| origin.parse())
+            .collect::<Result<Vec<OriginPattern>, _>>()?;
+
+        let allows_any_method = config
+            .allowed_methods
+            .iter()
+            .any(|method| method == CORS_WILDCARD);
+        let allowed_methods = config
+            .allowed_methods
+            .iter()
+            .filter(|method| *method != CORS_WILDCARD)
+            .map(|method| {
+                Method::from_str(method)
+                    .map_err(|_| InvalidCorsPolicy::InvalidMethod(method.clone()))
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (allows_any_header, allowed_headers) = parse_header_names(&config.allowed_headers)?;
+        let (exposes_any_header, exposed_headers) = parse_header_names(&config.exposed_headers)?;
+
+        if config.allow_credentials {
+            // browsers ignore the wildcards in credentialed responses
+            if origin_patterns.contains(&OriginPattern::Any) {
+                return Err(InvalidCorsPolicy::WildcardWithCredentials(
+                    "allowed_origins",
+                ));
+            }
+            if exposes_any_header {
+                return Err(InvalidCorsPolicy::WildcardWithCredentials(
+                    "exposed_headers",
+                ));
+            }
+        }
+
+        Ok(Self(Arc::new(CorsPolicyInner {
+            origin_patterns,
+            allowed_methods,
+            allowed_headers,
+            exposed_headers,
+            allows_any_method,
+            allows_any_header,
+            exposes_any_header,
+            allow_credentials: config.allow_credentials,
+            max_age: config.max_age_secs.map(Duration::from_secs),
+        })))
+    }
+
+    pub fn is_origin_allowed(&self, origin: &HeaderValue) -> bool {
+        origin.to_str().is_ok_and(|origin| {
+            self.0
+                .origin_patterns
+                .iter()
+                .any(|origin_pattern| origin_pattern.matches(origin))
+        })
+    }
+
+    /// Answers the preflight requests and adds the CORS headers to the responses
+    ///
+    /// With credentials the wildcard methods and headers are answered by mirroring the preflight
+    /// request, because browsers do not accept `*` in credentialed responses.
+    pub fn layer(&self) -> CorsLayer {
+        let inner = &self.0;
+
+        let allow_origin = if inner.origin_patterns.contains(&OriginPattern::Any) {
+            AllowOrigin::any()
+        } else {
+            let policy = self.clone();
+            AllowOrigin::predicate(move |origin, _request_parts| policy.is_origin_allowed(origin))
+        };
+
+        let allow_methods = match (inner.allows_any_method, inner.allow_credentials) {
+            (true, true) => AllowMethods::mirror_request(),
+            (true, false) => AllowMethods::any(),
+            (false, _) => AllowMethods::list(inner.allowed_methods.iter().cloned()),
+        };
+
+        let allow_headers = match (inner.allows_any_header, inner.allow_credentials) {
+            (true, true) => AllowHeaders::mirror_request(),
+            (true, false) => AllowHeaders::any(),
+            (false, _) => AllowHeaders::list(inner.allowed_headers.iter().cloned()),
+        };
+
+        let expose_headers = if inner.exposes_any_header {
+            ExposeHeaders::any()
+        } else {
+            ExposeHeaders::list(inner.exposed_headers.iter().cloned())
+        };
+
+        let layer = CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods(allow_methods)
+            .allow_headers(allow_headers)
+            .expose_headers(expose_headers)
+            .allow_credentials(inner.allow_credentials);
+
+        match inner.max_age {
+            Some(max_age) => layer.max_age(max_age),
+            None => layer,
+        }
+    }
+}
+
+/// Returns whether the list holds the wildcard and the header names of the rest of the list
+fn parse_header_names(
+    header_names: &[String],
+) -> Result<(bool, Vec<HeaderName>), InvalidCorsPolicy> {
+    let has_wildcard = header_names
+        .iter()
+        .any(|header_name| header_name == CORS_WILDCARD);
+
+    let header_names = header_names
+        .iter()
+        .filter(|header_name| *header_name != CORS_WILDCARD)
+        .map(|header_name| {
+            HeaderName::from_str(header_name)
+                .map_err(|_| InvalidCorsPolicy::InvalidHeaderName(header_name.clone()))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((has_wildcard, header_names))
+}
+
+pub mod error {
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum InvalidCorsPolicy {
+        #[error(
+            "InvalidCorsPolicy: expected '*' or an origin in the form of 'scheme://[*.]host[:port]', origin = '{0}'"
+        )]
+        InvalidOrigin(String),
+
+        #[error("InvalidCorsPolicy: invalid HTTP method, method = '{0}'")]
+        InvalidMethod(String),
+
+        #[error("InvalidCorsPolicy: invalid HTTP header name, header_name = '{0}'")]
+        InvalidHeaderName(String),
+
+        #[error("InvalidCorsPolicy: '*' cannot be used together with credentials, list = '{0}'")]
+        WildcardWithCredentials(&'static str),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(s: &str) -> OriginPattern {
+        s.parse().expect("valid origin pattern")
+    }
+
+    #[test]
+    fn subdomain_pattern_matches_subdomains_at_any_depth() {
+        let origin_pattern = pattern("https://*.example.com");
+
+        assert!(origin_pattern.matches("https://a.example.com"));
+        assert!(origin_pattern.matches("https://a.b.example.com"));
+        assert!(origin_pattern.matches("https://a-b.example.com"));
+    }
+
+    #[test]
+    fn subdomain_pattern_does_not_match_the_domain_itself() {
+        let origin_pattern = pattern("https://*.example.com");
+
+        assert!(!origin_pattern.matches("https://example.com"));
+        assert!(!origin_pattern.matches("https://.example.com"));
+    }
+
+    #[test]
+    fn subdomain_pattern_does_not_match_other_ports_schemes_or_domains() {
+        let origin_pattern = pattern("https://*.example.com");
+
+        assert!(!origin_pattern.matches("https://a.example.com:8443"));
+        assert!(!origin_pattern.matches("http://a.example.com"));
+        assert!(!origin_pattern.matches("https://evil-example.com"));
+        assert!(!origin_pattern.matches("https://a.example.com.evil.com"));
+        assert!(!origin_pattern.matches("https://a/b.example.com"));
+    }
+
+    #[test]
+    fn subdomain_pattern_with_port_matches_only_that_port() {
+        let origin_pattern = pattern("https://*.example.com:8443");
+
+        assert!(origin_pattern.matches("https://a.example.com:8443"));
+        assert!(!origin_pattern.matches("https://a.example.com"));
+        assert!(!origin_pattern.matches("https://a.example.com:443"));
+    }
+
+    #[test]
+    fn exact_pattern_matches_only_the_origin() {
+        let origin_pattern = pattern("https://example.com");
+
+        assert!(origin_pattern.matches("https://example.com"));
+        assert!(!origin_pattern.matches("https://a.example.com"));
+        assert!(!origin_pattern.matches("https://example.com:8443"));
+        assert!(!origin_pattern.matches("http://example.com"));
+    }
+
+    #[test]
+    fn patterns_and_origins_are_compared_case_insensitively() {
+        assert!(pattern("https://*.example.com").matches("HTTPS://A.EXAMPLE.COM"));
+        assert!(pattern("HTTPS://Example.com").matches("https://EXAMPLE.com"));
+    }
+
+    #[test]
+    fn wildcard_pattern_matches_every_origin() {
+        assert_eq!(pattern("*"), OriginPattern::Any);
+        assert!(pattern("*").matches("https://example.com"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        for invalid_pattern in [
+            "",
+            "example.com",
+            "ftp://example.com",
+            "https://",
+            "https://*",
+            "https://*.",
+            "https://a.*.example.com",
+            "https://*example.com",
+            "https://example..com",
+            "https://example.com:port",
+            "https://example.com:65536",
+            "https://example.com/",
+        ] {
+            assert!(
+                matches!(
+                    invalid_pattern.parse::<OriginPattern>(),
+                    Err(InvalidCorsPolicy::InvalidOrigin(_))
+                ),
+                "pattern = '{invalid_pattern}'"
+            );
+        }
+    }
+
+    #[test]
+    fn wildcard_origin_is_rejected_with_credentials() {
+        let config = CorsConfig {
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+
+        assert!(matches!(
+            CorsPolicy::new(&config),
+            Err(InvalidCorsPolicy::WildcardWithCredentials(
+                "allowed_origins"
+            ))
+        ));
+    }
+
+    #[test]
+    fn wildcard_exposed_headers_are_rejected_with_credentials() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".into()],
+            exposed_headers: vec![CORS_WILDCARD.into()],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+
+        assert!(matches!(
+            CorsPolicy::new(&config),
+            Err(InvalidCorsPolicy::WildcardWithCredentials(
+                "exposed_headers"
+            ))
+        ));
+    }
+
+    #[test]
+    fn policy_allows_the_origins_of_its_patterns() {
+        let config = CorsConfig {
+            allowed_origins: vec!["https://example.com".into(), "https://*.example.org".into()],
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+        let policy = CorsPolicy::new(&config).expect("valid policy");
+
+        assert!(policy.is_origin_allowed(&HeaderValue::from_static("https://example.com")));
+        assert!(policy.is_origin_allowed(&HeaderValue::from_static("https://a.example.org")));
+        assert!(!policy.is_origin_allowed(&HeaderValue::from_static("https://example.org")));
+        assert!(!policy.is_origin_allowed(&HeaderValue::from_static("https://evil.com")));
+    }
+}
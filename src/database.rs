@@ -1,8 +1,58 @@
-use std::{fs::create_dir, path::Path};
+use std::{
+    fs::{create_dir_all, read_dir, remove_file},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
-use sqlx::{migrate::MigrateDatabase, sqlite::SqliteSynchronous, Sqlite, SqlitePool};
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    SqlitePool,
+};
 
-use crate::error::DatabaseOpenError;
+use crate::error::{DatabaseOpenError, PurgeDatabaseError};
+
+/// Name of the database file inside the database folder
+pub const DB_FILE_NAME: &str = "db.sqlite";
+
+/// Suffixes of the files that SQLite keeps next to the database file
+const DB_FILE_SIBLING_SUFFIXES: [&str; 3] = ["-wal", "-shm", "-journal"];
+
+static MIGRATOR: Migrator = sqlx::migrate!("./db-migrations");
+
+/// Where the data of the database is kept
+#[derive(Debug, Clone)]
+pub enum DatabaseLocation {
+    /// The database file is kept in the folder, the folder is created when it does not exist
+    Folder(PathBuf),
+    /// The database lives as long as the process, shared by every connection of the pool
+    InMemory,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseOptions {
+    pub location: DatabaseLocation,
+    pub max_connections: u32,
+    /// How long a connection waits for a locked database before the statement fails
+    pub busy_timeout: Duration,
+    /// Ignored for in-memory databases, their journal is always kept in memory
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+}
+
+impl DatabaseOptions {
+    /// Options of a database kept in the folder, with the default settings of the command line
+    pub fn in_folder(folder_path: impl AsRef<Path>) -> Self {
+        Self {
+            location: DatabaseLocation::Folder(folder_path.as_ref().to_owned()),
+            max_connections: 10,
+            busy_timeout: Duration::from_secs(5),
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Full,
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct Database {
@@ -10,29 +60,132 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn open(folder_path: impl AsRef<Path>) -> Result<Self, DatabaseOpenError> {
-        let mut db_url = folder_path.as_ref().to_owned();
-        db_url.push("db.sqlite");
-        let db_url = db_url.to_string_lossy();
-
-        if !Sqlite::database_exists(&db_url).await? {
-            let _ = create_dir(&folder_path);
-            Sqlite::create_database(&db_url).await?;
-        }
+    pub async fn open(options: &DatabaseOptions) -> Result<Self, DatabaseOpenError> {
+        let pool_options = SqlitePoolOptions::new().max_connections(options.max_connections);
 
-        let connection_pool = SqlitePool::connect(&db_url).await?;
-        let connection_options = connection_pool.connect_options().as_ref().clone();
-        connection_pool
-            .set_connect_options(connection_options.synchronous(SqliteSynchronous::Full));
+        let (connect_options, pool_options) = match &options.location {
+            DatabaseLocation::Folder(folder_path) => {
+                create_dir_all(folder_path)?;
 
-        sqlx::migrate!("./db-migrations")
-            .run(&connection_pool)
+                (
+                    SqliteConnectOptions::new()
+                        .filename(folder_path.join(DB_FILE_NAME))
+                        .create_if_missing(true)
+                        .journal_mode(options.journal_mode),
+                    pool_options,
+                )
+            }
+            DatabaseLocation::InMemory => (
+                SqliteConnectOptions::from_str("sqlite::memory:")?,
+                // the in-memory database is dropped together with its last connection
+                pool_options
+                    .min_connections(1)
+                    .idle_timeout(None)
+                    .max_lifetime(None),
+            ),
+        };
+
+        let connection_pool = pool_options
+            .connect_with(
+                connect_options
+                    .busy_timeout(options.busy_timeout)
+                    .synchronous(options.synchronous),
+            )
             .await?;
 
+        MIGRATOR.run(&connection_pool).await?;
+
         Ok(Self { connection_pool })
     }
 
+    /// Lists the files of the database in the folder, the database file and its `-wal`, `-shm`
+    /// and `-journal` siblings
+    ///
+    /// Fails when the folder holds anything else, so a mistyped path cannot lead to deleting
+    /// unrelated data. A missing folder holds no files.
+    pub fn files_in_folder(folder_path: &Path) -> Result<Vec<PathBuf>, PurgeDatabaseError> {
+        let entries = match read_dir(folder_path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => {
+                return Err(PurgeDatabaseError::CannotReadFolder {
+                    path: folder_path.to_owned(),
+                    source,
+                })
+            }
+        };
+
+        let database_file_names: Vec<_> = [DB_FILE_NAME.to_owned()]
+            .into_iter()
+            .chain(
+                DB_FILE_SIBLING_SUFFIXES
+                    .iter()
+                    .map(|suffix| format!("{DB_FILE_NAME}{suffix}")),
+            )
+            .collect();
+
+        let mut database_files = Vec::new();
+        let mut unexpected_files = Vec::new();
+
+        for entry in entries {
+            let entry = entry.map_err(|source| PurgeDatabaseError::CannotReadFolder {
+                path: folder_path.to_owned(),
+                source,
+            })?;
+
+            let is_database_file = entry.file_type().is_ok_and(|file_type| file_type.is_file())
+                && database_file_names
+                    .iter()
+                    .any(|file_name| entry.file_name() == file_name.as_str());
+
+            if is_database_file {
+                database_files.push(entry.path());
+            } else {
+                unexpected_files.push(entry.path());
+            }
+        }
+
+        if !unexpected_files.is_empty() {
+            return Err(PurgeDatabaseError::UnexpectedFiles {
+                path: folder_path.to_owned(),
+                files: unexpected_files,
+            });
+        }
+
+        database_files.sort();
+
+        Ok(database_files)
+    }
+
+    /// Deletes the files of the database from the folder, the folder itself is kept
+    ///
+    /// Nothing is deleted when the folder holds files that do not belong to the database, see
+    /// [`Database::files_in_folder`]. Returns the deleted files.
+    pub fn purge(folder_path: &Path) -> Result<Vec<PathBuf>, PurgeDatabaseError> {
+        let database_files = Self::files_in_folder(folder_path)?;
+
+        for path in &database_files {
+            remove_file(path).map_err(|source| PurgeDatabaseError::CannotRemoveFile {
+                path: path.clone(),
+                source,
+            })?;
+        }
+
+        Ok(database_files)
+    }
+
+    /// Version of the last migration in `db-migrations`, the version of an up-to-date database
+    pub fn expected_migration_version() -> Option<i64> {
+        MIGRATOR.iter().map(|migration| migration.version).max()
+    }
+
     pub fn connection_pool_ref(&self) -> &SqlitePool {
         &self.connection_pool
     }
+
+    /// Waits for the connections in use to be returned and closes every connection, the last
+    /// connection checkpoints the WAL into the database file
+    pub async fn close(&self) {
+        self.connection_pool.close().await;
+    }
 }
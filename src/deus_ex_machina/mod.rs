@@ -1,11 +1,18 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use async_graphql::{
-    extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
-    ServerResult, Value,
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextPrepareRequest,
+        NextRequest, NextResolve, NextSubscribe, ResolveInfo,
+    },
+    futures_util::stream::BoxStream,
+    parser::types::{DocumentOperations, ExecutableDocument, OperationType},
+    Request, Response, ServerResult, Value, Variables,
 };
+use futures_util::StreamExt;
+use parking_lot::Mutex;
 
-use crate::state::State;
+use crate::{metrics::SubscriptionGuard, state::State};
 
 struct DeusExMachinaInner {
     state: State,
@@ -17,8 +24,118 @@ impl DeusExMachinaInner {
     }
 }
 
+/// What is known about the operation of the request, filled in while the request is processed
+#[derive(Default)]
+struct OperationInfo {
+    requested_name: Option<String>,
+    ty: Option<OperationType>,
+    name: Option<String>,
+    /// Keeps the subscription counted as long as the extension of its request lives
+    subscription_guard: Option<SubscriptionGuard>,
+}
+
+/// Created for every request, so the operation of the request can be remembered between the hooks
+struct DeusExMachinaExtension {
+    inner: Arc<DeusExMachinaInner>,
+    operation: Mutex<OperationInfo>,
+}
+
 #[async_trait::async_trait]
-impl Extension for DeusExMachinaInner {
+impl Extension for DeusExMachinaExtension {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let started_at = Instant::now();
+        let response = next.run(ctx).await;
+
+        let metrics = &self.inner.state.metrics;
+        {
+            let operation = self.operation.lock();
+            metrics.observe_operation(
+                match operation.ty {
+                    Some(OperationType::Query) => "query",
+                    Some(OperationType::Mutation) => "mutation",
+                    Some(OperationType::Subscription) => "subscription",
+                    None => "unknown",
+                },
+                operation.name.as_deref().unwrap_or("anonymous"),
+                started_at.elapsed(),
+            );
+        }
+        metrics.count_errors(&response.errors);
+
+        response
+    }
+
+    fn subscribe<'s>(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        stream: BoxStream<'s, Response>,
+        next: NextSubscribe<'_>,
+    ) -> BoxStream<'s, Response> {
+        let metrics = self.inner.state.metrics.clone();
+
+        next.run(ctx, stream)
+            .inspect(move |response| metrics.count_errors(&response.errors))
+            .boxed()
+    }
+
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        self.operation.lock().requested_name = request.operation_name.clone();
+
+        next.run(ctx, request).await
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        let mut operation = self.operation.lock();
+        let selected_operation = match (&document.operations, operation.requested_name.as_deref()) {
+            (DocumentOperations::Single(operation_definition), _) => {
+                Some((None, operation_definition.node.ty))
+            }
+            (DocumentOperations::Multiple(operation_definitions), Some(requested_name)) => {
+                operation_definitions
+                    .get(requested_name)
+                    .map(|operation_definition| {
+                        (
+                            Some(requested_name.to_owned()),
+                            operation_definition.node.ty,
+                        )
+                    })
+            }
+            (DocumentOperations::Multiple(operation_definitions), None) => {
+                let mut operation_definitions = operation_definitions.iter();
+                match (operation_definitions.next(), operation_definitions.next()) {
+                    (Some((name, operation_definition)), None) => {
+                        Some((Some(name.to_string()), operation_definition.node.ty))
+                    }
+                    _ => None,
+                }
+            }
+        };
+
+        if let Some((name, ty)) = selected_operation {
+            if ty == OperationType::Subscription {
+                operation.subscription_guard = Some(self.inner.state.metrics.track_subscription());
+            }
+
+            operation.name = name;
+            operation.ty = Some(ty);
+        }
+
+        Ok(document)
+    }
+
     async fn resolve(
         &self,
         ctx: &ExtensionContext<'_>,
@@ -29,7 +146,21 @@ impl Extension for DeusExMachinaInner {
             "resolving field, path = {:?}",
             info.path_node.to_string_vec()
         );
-        next.run(ctx, info).await
+
+        if info.is_for_introspection {
+            return next.run(ctx, info).await;
+        }
+
+        let (parent_type, field_name) = (info.parent_type, info.name);
+        let started_at = Instant::now();
+        let result = next.run(ctx, info).await;
+
+        self.inner
+            .state
+            .metrics
+            .observe_field(parent_type, field_name, started_at.elapsed());
+
+        result
     }
 }
 
@@ -43,6 +174,9 @@ impl DeusExMachina {
 
 impl ExtensionFactory for DeusExMachina {
     fn create(&self) -> Arc<dyn Extension> {
-        self.0.clone()
+        Arc::new(DeusExMachinaExtension {
+            inner: self.0.clone(),
+            operation: Mutex::new(OperationInfo::default()),
+        })
     }
 }
@@ -1,4 +1,37 @@
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
+
+use async_graphql::{ErrorExtensions, Value};
+
+pub const INTERNAL_ERROR_CODE: &str = "INTERNAL";
+
+/// Maps errors to stable, machine-readable codes that are sent in the GraphQL error `extensions`
+///
+/// Errors with the [`INTERNAL_ERROR_CODE`] code are sent to the clients as [`InternalError`].
+pub trait ErrorCode: std::error::Error + 'static {
+    fn code(&self) -> &'static str;
+
+    /// Structured fields describing the error, sent next to the `code`
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        Vec::new()
+    }
+
+    fn to_graphql_error(&self) -> async_graphql::Error
+    where
+        Self: Sized,
+    {
+        let code = self.code();
+        if code == INTERNAL_ERROR_CODE {
+            return InternalError::new(self).extend();
+        }
+
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", code);
+            for (name, value) in self.fields() {
+                extensions.set(name, value);
+            }
+        })
+    }
+}
 
 pub trait SqlxErrorConverter<T> {
     fn to_sqlx_error_result(self) -> Result<T, SqlxError>;
@@ -15,7 +48,13 @@ impl<T> SqlxErrorConverter<T> for Result<T, sqlx::Error> {
 
 #[derive(Clone, Debug, thiserror::Error)]
 #[error("SqlxError: '{0}'")]
-pub struct SqlxError(pub Arc<sqlx::Error>);
+pub struct SqlxError(#[source] pub Arc<sqlx::Error>);
+
+impl ErrorCode for SqlxError {
+    fn code(&self) -> &'static str {
+        INTERNAL_ERROR_CODE
+    }
+}
 
 impl From<sqlx::Error> for SqlxError {
     fn from(value: sqlx::Error) -> Self {
@@ -38,8 +77,81 @@ pub enum DatabaseOpenError {
         #[source]
         sqlx::migrate::MigrateError,
     ),
+
+    #[error("DatabaseOpenError: '{0}'")]
+    CannotCreateFolder(
+        #[from]
+        #[source]
+        std::io::Error,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PurgeDatabaseError {
+    #[error("PurgeDatabaseError: cannot read folder = '{path:?}', error = '{source}'")]
+    CannotReadFolder {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error(
+        "PurgeDatabaseError: folder = '{path:?}' contains files that do not belong to the database, files = '{files:?}'"
+    )]
+    UnexpectedFiles { path: PathBuf, files: Vec<PathBuf> },
+
+    #[error("PurgeDatabaseError: cannot remove file = '{path:?}', error = '{source}'")]
+    CannotRemoveFile {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("PurgeDatabaseError: the purge was not confirmed")]
+    NotConfirmed,
 }
 
 #[derive(Clone, Debug, thiserror::Error)]
 #[error("UnimplementedError: '{0}'")]
 pub struct UnimplementedError(pub String);
+
+/// Error sent to the clients in place of errors whose details must not leak
+///
+/// The full source chain is logged together with the correlation id, the chain is only sent to the
+/// clients when the `expose-internal-error` feature is enabled.
+#[derive(Clone, Debug, thiserror::Error)]
+#[error("InternalError: correlation_id = '{correlation_id}'")]
+pub struct InternalError {
+    pub correlation_id: String,
+    pub source_chain: Vec<String>,
+}
+
+impl InternalError {
+    pub fn new(error: &(dyn std::error::Error + 'static)) -> Self {
+        let correlation_id = uuid::Uuid::new_v4().to_string();
+        let source_chain = std::iter::successors(Some(error), |error| error.source())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        log::error!(
+            "InternalError: correlation_id = '{correlation_id}', source_chain = {source_chain:?}"
+        );
+
+        Self {
+            correlation_id,
+            source_chain,
+        }
+    }
+}
+
+impl ErrorExtensions for InternalError {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, extensions| {
+            extensions.set("code", INTERNAL_ERROR_CODE);
+            extensions.set("correlationId", self.correlation_id.as_str());
+
+            #[cfg(feature = "expose-internal-error")]
+            extensions.set("sourceChain", self.source_chain.clone());
+        })
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    future::ready,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use async_graphql::{
+    http::{WebSocket as GraphQLWebSocketStream, WebSocketProtocols, WsMessage},
+    Data, Executor,
+};
+use async_graphql_axum::GraphQLProtocol;
+use axum::extract::ws::{CloseFrame, Message, WebSocket};
+use futures_util::{SinkExt, StreamExt};
+
+use crate::{auth::Authenticator, log_location, shutdown::Shutdown};
+
+/// Close code of the `graphql-transport-ws` protocol for rejected connections
+const CLOSE_CODE_FORBIDDEN: u16 = 4403;
+
+/// Close code of the WebSocket protocol for endpoints that are going away
+const CLOSE_CODE_GOING_AWAY: u16 = 1001;
+
+/// Serves a GraphQL over WebSocket connection
+///
+/// The caller is authenticated by the token sent in the `connection_init` payload, connections
+/// with a missing or invalid token are closed. The connection is closed with `1001: Going Away`
+/// when the server shuts down, the shutdown waits for it.
+pub async fn serve_graphql_ws<E: Executor>(
+    socket: WebSocket,
+    executor: E,
+    protocol: GraphQLProtocol,
+    authenticator: Authenticator,
+    shutdown: Shutdown,
+) {
+    let _task_guard = shutdown.track_task();
+
+    let (mut sink, stream) = socket.split();
+
+    let input = stream
+        .take_while(|message| ready(message.is_ok()))
+        .filter_map(|message| {
+            ready(match message {
+                Ok(Message::Text(text)) => Some(text.into_bytes()),
+                Ok(Message::Binary(bytes)) => Some(bytes),
+                _ => None,
+            })
+        });
+
+    let connection_rejected = Arc::new(AtomicBool::new(false));
+
+    let on_connection_init = {
+        let connection_rejected = connection_rejected.clone();
+
+        move |payload: serde_json::Value| async move {
+            match authenticator.verify_connection_init_payload(&payload) {
+                Ok(authenticated_user) => {
+                    let mut data = Data::default();
+                    data.insert(authenticated_user);
+                    Ok(data)
+                }
+                Err(e) => {
+                    log::debug!("{}, rejected connection_init, error = {e}", log_location!());
+                    connection_rejected.store(true, Ordering::Relaxed);
+                    Err(async_graphql::Error::new("Forbidden"))
+                }
+            }
+        }
+    };
+
+    let mut outgoing = GraphQLWebSocketStream::new(executor, input, protocol.0)
+        .on_connection_init(on_connection_init)
+        .map(|message| match message {
+            WsMessage::Text(text) => Message::Text(text),
+            WsMessage::Close(code, reason) => {
+                let code = if protocol.0 == WebSocketProtocols::GraphQLWS
+                    && connection_rejected.load(Ordering::Relaxed)
+                {
+                    CLOSE_CODE_FORBIDDEN
+                } else {
+                    code
+                };
+
+                Message::Close(Some(CloseFrame {
+                    code,
+                    reason: reason.into(),
+                }))
+            }
+        });
+
+    loop {
+        tokio::select! {
+            message = outgoing.next() => {
+                let Some(message) = message else {
+                    break;
+                };
+
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+            _ = shutdown.triggered() => {
+                let _ = sink
+                    .send(Message::Close(Some(CloseFrame {
+                        code: CLOSE_CODE_GOING_AWAY,
+                        reason: "the server is shutting down".into(),
+                    })))
+                    .await;
+
+                break;
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use axum::{extract::State as AxumState, http::StatusCode, routing::get, Extension, Json, Router};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    database::Database, log_location, shutdown::Shutdown,
+    sql_queries::sql_migration::get_applied_migration_version, state::State,
+};
+
+/// How long the readiness check waits for the database, a probe has to be answered quickly
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Identifies the running build
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+    pub version: &'static str,
+    pub git_commit: &'static str,
+    /// SHA-256 of the SDL of the GraphQL schema, changes whenever the API changes
+    pub schema_hash: String,
+}
+
+impl VersionInfo {
+    pub fn new(sdl: &str) -> Self {
+        let schema_hash = Sha256::digest(sdl.as_bytes())
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_commit: env!("GIT_COMMIT"),
+            schema_hash: format!("sha256:{schema_hash}"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HealthResponse {
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadinessResponse {
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    migration_version: Option<i64>,
+    expected_migration_version: Option<i64>,
+}
+
+/// Routes of the probes of the orchestrator, `/healthz`, `/readyz` and `/version`
+pub fn health_routes(state: State, shutdown: Shutdown, version_info: VersionInfo) -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/version", get(version))
+        .layer(Extension(shutdown))
+        .layer(Extension(version_info))
+        .with_state(state)
+}
+
+/// The process is alive and answers requests
+async fn healthz() -> Json<HealthResponse> {
+    Json(HealthResponse { status: "ok" })
+}
+
+/// The database answers and its migrations are at the version of the binary, the server is not
+/// ready while it shuts down
+async fn readyz(
+    AxumState(state): AxumState<State>,
+    Extension(shutdown): Extension<Shutdown>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let expected_migration_version = Database::expected_migration_version();
+
+    let not_ready = |reason: String, migration_version| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ReadinessResponse {
+                status: "unavailable",
+                reason: Some(reason),
+                migration_version,
+                expected_migration_version,
+            }),
+        )
+    };
+
+    if shutdown.is_triggered() {
+        return not_ready("the server is shutting down".into(), None);
+    }
+
+    let migration_version = match tokio::time::timeout(
+        READINESS_TIMEOUT,
+        get_applied_migration_version(&state),
+    )
+    .await
+    {
+        Ok(Ok(migration_version)) => migration_version,
+        // the error is logged by the query, its details are not exposed
+        Ok(Err(_)) => return not_ready("the database cannot be queried".into(), None),
+        Err(_) => {
+            log::error!("{}, the readiness check has timed out", log_location!());
+            return not_ready("the database did not answer in time".into(), None);
+        }
+    };
+
+    if migration_version != expected_migration_version {
+        return not_ready(
+            "the migrations of the database are not at the expected version".into(),
+            migration_version,
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Json(ReadinessResponse {
+            status: "ready",
+            reason: None,
+            migration_version,
+            expected_migration_version,
+        }),
+    )
+}
+
+async fn version(Extension(version_info): Extension<VersionInfo>) -> Json<VersionInfo> {
+    Json(version_info)
+}
@@ -3,66 +3,142 @@
 #![allow(clippy::diverging_sub_expression)]
 #![allow(clippy::unreachable)]
 
+mod auth;
+mod benchmark;
 mod cli;
+mod config;
+mod cors;
 mod database;
 mod deus_ex_machina;
 mod error;
+mod graphql_ws;
+mod health;
+mod metrics;
 mod model;
+mod shutdown;
 mod sql_queries;
 mod state;
 mod utils;
 
-use std::{convert::Infallible, fs::remove_dir_all, net::ToSocketAddrs};
+use std::{net::ToSocketAddrs, path::Path};
 
 use async_graphql::{http::GraphiQLSource, SDLExportOptions, Schema};
-use async_graphql_axum::{GraphQL, GraphQLSubscription};
+use async_graphql_axum::{
+    GraphQLProtocol, GraphQLRequest, GraphQLResponse, ALL_WEBSOCKET_PROTOCOLS,
+};
+use auth::{authentication_middleware, AuthenticatedUser, Authenticator};
 use axum::{
-    response::Html,
-    routing::{get, post_service},
-    Router,
+    extract::{DefaultBodyLimit, WebSocketUpgrade},
+    http::{header::ORIGIN, HeaderMap, StatusCode},
+    middleware::from_fn_with_state,
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+    Extension, Router,
 };
-use axum_helpers::{app::AxumApp, response_http_header_mutator::ResponseHttpHeaderMutatorLayer};
+use benchmark::bench_characters;
 use clap::Parser;
-use cli::{Cli, Commands, SchemaSource};
+use cli::{ask_confirmation, Cli, Commands, ConfigCommands, SchemaSource};
+use config::{Config, LimitsConfig};
+use cors::CorsPolicy;
+use database::{Database, DatabaseLocation};
 use deus_ex_machina::DeusExMachina;
+use error::PurgeDatabaseError;
 use graphql_cli_tools::schema_diff::diff_schema;
-use model::{mutation::Mutation, query::Query, subscription::Subscription};
+use graphql_ws::serve_graphql_ws;
+use health::{health_routes, VersionInfo};
+use metrics::metrics_routes;
+use model::{
+    enums::role::Role, loaders::DataLoaders, mutation::Mutation, query::Query,
+    subscription::Subscription,
+};
+use shutdown::{shutdown_signal, Shutdown};
+use sql_queries::sql_user_role::grant_role;
 use state::State;
-use tower_http::trace::TraceLayer;
-
-fn routes(state: State, schema: Schema<Query, Mutation, Subscription>) -> Router {
-    let preflight_middleware = ResponseHttpHeaderMutatorLayer::new(|_req_headers, res_headers| {
-        res_headers.insert(
-            "Access-Control-Allow-Methods",
-            "*".parse().expect("cannot parse HTTP header value"),
-        );
-        res_headers.insert(
-            "Access-Control-Allow-Headers",
-            "*".parse().expect("cannot parse HTTP header value"),
-        );
-        res_headers.insert(
-            "Access-Control-Allow-Origin",
-            "*".parse().expect("cannot parse HTTP header value"),
-        );
-
-        Ok::<(), Infallible>(())
-    });
+use tokio::net::TcpListener;
+use tower_http::{limit::RequestBodyLimitLayer, trace::TraceLayer};
+
+fn routes(
+    state: State,
+    schema: Schema<Query, Mutation, Subscription>,
+    authenticator: Authenticator,
+    cors_policy: CorsPolicy,
+    shutdown: Shutdown,
+    version_info: VersionInfo,
+    limits: &LimitsConfig,
+) -> Router {
+    // the probes and the scraper poll frequently, so they are kept out of the trace log
+    let health_routes = health_routes(state.clone(), shutdown.clone(), version_info);
+    let metrics_routes = metrics_routes(state.clone());
 
     Router::new()
         .route("/", get(index_page))
         .route("/graphiql", get(graphiql))
-        .route_service("/api/graphql-ws", GraphQLSubscription::new(schema.clone()))
+        .route("/api/graphql-ws", get(graphql_ws))
         .route(
             "/api/graphql",
-            post_service(GraphQL::new(schema.clone()))
-                .options(options_graphql)
-                .route_layer(preflight_middleware.clone()),
+            post(graphql).route_layer(from_fn_with_state(
+                authenticator.clone(),
+                authentication_middleware,
+            )),
         )
+        .layer(Extension(schema))
+        .layer(Extension(authenticator))
+        .layer(Extension(cors_policy.clone()))
+        .layer(Extension(shutdown))
+        // GraphQLRequest reads the body without the limit of the extractors, so the body itself is
+        // limited, which also replaces the default limit of the extractors
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(limits.max_request_body_bytes))
+        .layer(cors_policy.layer())
         .layer(TraceLayer::new_for_http())
         .with_state(state)
+        .merge(health_routes)
+        .merge(metrics_routes)
 }
 
-async fn options_graphql() {}
+async fn graphql(
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
+    authenticated_user: Option<Extension<AuthenticatedUser>>,
+    request: GraphQLRequest,
+) -> GraphQLResponse {
+    let mut request = request.into_inner();
+    if let Some(Extension(authenticated_user)) = authenticated_user {
+        request = request.data(authenticated_user);
+    }
+
+    schema.execute(request).await.into()
+}
+
+async fn graphql_ws(
+    Extension(schema): Extension<Schema<Query, Mutation, Subscription>>,
+    Extension(authenticator): Extension<Authenticator>,
+    Extension(cors_policy): Extension<CorsPolicy>,
+    Extension(shutdown): Extension<Shutdown>,
+    headers: HeaderMap,
+    protocol: GraphQLProtocol,
+    websocket: WebSocketUpgrade,
+) -> Response {
+    // browsers do not apply CORS to WebSockets, so the origin is checked before the upgrade
+    if let Some(origin) = headers.get(ORIGIN) {
+        if !cors_policy.is_origin_allowed(origin) {
+            log::debug!(
+                "{}, rejected WebSocket upgrade, origin = {origin:?}",
+                log_location!()
+            );
+            return StatusCode::FORBIDDEN.into_response();
+        }
+    }
+
+    if shutdown.is_triggered() {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| {
+            serve_graphql_ws(socket, schema, protocol, authenticator, shutdown)
+        })
+}
 
 async fn index_page() -> Html<String> {
     Html(
@@ -86,56 +162,224 @@ async fn graphiql() -> Html<String> {
     )
 }
 
-fn create_schema(state: State) -> Schema<Query, Mutation, Subscription> {
+fn create_schema(state: State, limits: &LimitsConfig) -> Schema<Query, Mutation, Subscription> {
     let query = Query {
         state: state.clone(),
     };
     let mutation = Mutation {
-        _state: state.clone(),
+        state: state.clone(),
     };
     let subscription = Subscription {
         _state: state.clone(),
     };
-    Schema::build(query, mutation, subscription)
+    // the typed ids are all exposed as the `ID` scalar
+    let mut schema_builder =
+        Schema::build_with_ignore_name_conflicts(query, mutation, subscription, ["ID"]);
+    if let Some(max_query_depth) = limits.max_query_depth {
+        schema_builder = schema_builder.limit_depth(max_query_depth);
+    }
+    if let Some(max_query_complexity) = limits.max_query_complexity {
+        schema_builder = schema_builder.limit_complexity(max_query_complexity);
+    }
+
+    schema_builder
+        .data(state.clone())
+        .extension(DataLoaders::new(state.clone()))
         .extension(DeusExMachina::new(state))
         .finish()
 }
 
+/// Deletes the files of the database after the user confirmed the deletion
+fn purge_database(db_path: &Path, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let database_files = Database::files_in_folder(db_path)?;
+    if database_files.is_empty() {
+        log::info!("there is no database to purge, db_path = {db_path:?}");
+        return Ok(());
+    }
+
+    if !yes {
+        println!("The following files will be deleted:");
+        for path in &database_files {
+            println!("  {}", path.display());
+        }
+
+        if !ask_confirmation("Delete the files?")? {
+            return Err(PurgeDatabaseError::NotConfirmed.into());
+        }
+    }
+
+    for path in Database::purge(db_path)? {
+        log::info!("purged database file = {path:?}");
+    }
+
+    Ok(())
+}
+
+/// Serves the API until the process is asked to stop, then stops accepting connections, waits
+/// for the in-flight requests and the WebSocket connections until the shutdown deadline, and
+/// closes the database
+async fn serve(
+    state: State,
+    schema: Schema<Query, Mutation, Subscription>,
+    config: &Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let authenticator = Authenticator::new(config.jwt_secret()?);
+    let cors_policy = CorsPolicy::new(&config.cors)?;
+    let shutdown = Shutdown::new();
+    let version_info = VersionInfo::new(&schema.sdl());
+    let router = routes(
+        state.clone(),
+        schema,
+        authenticator,
+        cors_policy,
+        shutdown.clone(),
+        version_info,
+        &config.limits,
+    );
+
+    let mut servers = Vec::new();
+    for addr in config.listener_address()?.to_socket_addrs()? {
+        let listener = match TcpListener::bind(addr).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!(
+                    "{}, could not listen on address = {addr}, error = {e:?}",
+                    log_location!()
+                );
+                continue;
+            }
+        };
+
+        log::info!("listening on address = {addr}");
+
+        let router = router.clone();
+        let shutdown = shutdown.clone();
+        servers.push(tokio::spawn(async move {
+            axum::serve(listener, router)
+                .with_graceful_shutdown(async move { shutdown.triggered().await })
+                .await
+        }));
+    }
+
+    if servers.is_empty() {
+        return Err("could not listen on any of the addresses".into());
+    }
+
+    shutdown_signal().await;
+
+    let shutdown_timeout = config.shutdown_timeout();
+    log::info!("shutting down, waiting for the in-flight work, timeout = {shutdown_timeout:?}");
+    shutdown.trigger();
+
+    // closing the database waits for the connections in use, so it has to fit in the same timeout
+    let deadline = tokio::time::Instant::now() + shutdown_timeout;
+
+    let drained = tokio::time::timeout_at(deadline, async {
+        for server in servers {
+            match server.await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => log::error!("{}, server failed, error = {e:?}", log_location!()),
+                Err(e) => log::error!("{}, server task failed, error = {e:?}", log_location!()),
+            }
+        }
+
+        shutdown.tasks_finished().await;
+    })
+    .await;
+
+    if drained.is_err() {
+        log::warn!("the shutdown timeout has passed, the remaining connections are dropped");
+    }
+
+    match tokio::time::timeout_at(deadline, state.database.close()).await {
+        Ok(()) => log::info!("the database is closed"),
+        Err(_) => log::warn!(
+            "the shutdown timeout has passed before the database could be closed, the connections still in use are dropped"
+        ),
+    }
+
+    Ok(())
+}
+
+/// Prints the effective configuration and the problems that would stop the server from starting,
+/// exits with status 1 when there is any
+fn check_config(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", config.to_redacted_toml()?);
+
+    if let Err(invalid_config) = config.validate_for_serving() {
+        for e in &invalid_config.errors {
+            eprintln!("{e}");
+        }
+
+        std::process::exit(1);
+    }
+
+    eprintln!("the configuration is valid");
+
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+
+    // the benchmark installs its own logger and works on temporary databases
+    if let Commands::BenchCharacters(params) = &cli.command {
+        return Ok(bench_characters(params).await?);
+    }
+
+    let config = Config::load(&cli.config_params)?;
+
+    if let Commands::Config(ConfigCommands::Check) = &cli.command {
+        check_config(&config)?;
+        return Ok(());
+    }
+
+    config.validate()?;
+
     env_logger::Builder::new()
-        // .filter_level(log::LevelFilter::Debug)
-        .filter_module("axum_helpers", log::LevelFilter::Debug)
-        .filter_module("graphql-example", log::LevelFilter::Trace)
-        .filter_module("tower_http", log::LevelFilter::Debug)
+        .parse_filters(&config.logging.filter)
         .init();
 
-    let cli = Cli::parse();
-
-    let db_path = "db";
+    let database_options = config.database_options();
 
     if cli.purge_db {
-        let _ = remove_dir_all(db_path);
+        match &database_options.location {
+            DatabaseLocation::Folder(db_path) => purge_database(db_path, cli.yes)?,
+            DatabaseLocation::InMemory => {
+                log::info!("the database is kept in memory, there is nothing to purge")
+            }
+        }
     }
 
-    let state = State::new(db_path).await?;
-    let schema = create_schema(state.clone());
+    let state = State::new(&database_options).await?;
+    let schema = create_schema(state.clone(), &config.limits);
 
     match cli.command {
-        Commands::Serve(params) => {
-            log::info!("starting application in server mode");
+        Commands::Serve => {
+            config.validate_for_serving()?;
 
-            let mut app = AxumApp::new(routes(state, schema));
-            for addr in params.listener_address.to_socket_addrs()? {
-                let _ = app.spawn_server(addr).await.inspect_err(|e| {
-                    log::error!(
-                        "{}, could not listen on address = {addr}, error = {e:?}",
-                        log_location!()
-                    );
-                });
-            }
+            log::info!("starting application in server mode");
 
-            app.join().await;
+            serve(state, schema, &config).await?;
+        }
+        Commands::IssueToken(params) => {
+            let authenticator = Authenticator::new(config.jwt_secret()?);
+            println!(
+                "{}",
+                authenticator.issue_token(params.user_id.as_id(), config.token_valid_for())?
+            );
+        }
+        Commands::GrantRole(params) => {
+            let roles = grant_role(&state, params.user_id.as_id(), params.role).await?;
+            println!(
+                "{}",
+                roles
+                    .iter()
+                    .map(Role::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
         }
         Commands::Sdl => {
             println!(
@@ -159,6 +403,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }
             }
         }
+        Commands::BenchCharacters(_) => unreachable!("the benchmark is run before the logger"),
+        Commands::Config(_) => unreachable!("the configuration is checked before the logger"),
     }
 
     Ok(())
@@ -0,0 +1,245 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_graphql::{ServerError, Value};
+use axum::{
+    extract::State as AxumState,
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use parking_lot::Mutex;
+use prometheus::{
+    core::Collector, exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounterVec,
+    IntGauge, Opts, Registry, TextEncoder,
+};
+
+use crate::{database::Database, log_location, state::State};
+
+/// Label of the errors that do not carry a `code` in their extensions (e.g., parse errors)
+const UNSPECIFIED_ERROR_CODE: &str = "UNSPECIFIED";
+
+/// The operation names are chosen by the clients, only the first ones get their own series
+const MAX_OPERATION_NAME_COUNT: usize = 100;
+
+/// Longer operation names do not get their own series
+const MAX_OPERATION_NAME_LENGTH: usize = 64;
+
+/// Label of the operations whose names do not get their own series
+const OTHER_OPERATION_NAME: &str = "other";
+
+struct MetricsInner {
+    registry: Registry,
+    operation_duration: HistogramVec,
+    /// Operation names that have their own series
+    operation_names: Mutex<HashSet<String>>,
+    field_duration: HistogramVec,
+    errors: IntCounterVec,
+    active_subscriptions: IntGauge,
+    pool_connections: IntGauge,
+    pool_idle_connections: IntGauge,
+    pool_max_connections: IntGauge,
+}
+
+/// Collects the metrics of the GraphQL API and the database, exposed in the Prometheus text format
+#[derive(Clone)]
+pub struct Metrics(Arc<MetricsInner>);
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let register = |collector: Box<dyn Collector>| {
+            registry
+                .register(collector)
+                .expect("cannot register metric");
+        };
+
+        let operation_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "graphql_operation_duration_seconds",
+                "Duration of the GraphQL operations, from parsing until the response",
+            ),
+            &["operation_type", "operation_name"],
+        )
+        .expect("cannot create metric");
+        register(Box::new(operation_duration.clone()));
+
+        let field_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "graphql_field_duration_seconds",
+                "Duration of resolving the GraphQL fields, including their selection sets",
+            )
+            .buckets(exponential_buckets(0.0001, 4.0, 9).expect("cannot create buckets")),
+            &["parent_type", "field_name"],
+        )
+        .expect("cannot create metric");
+        register(Box::new(field_duration.clone()));
+
+        let errors = IntCounterVec::new(
+            Opts::new(
+                "graphql_errors_total",
+                "Number of the errors sent to the clients",
+            ),
+            &["code"],
+        )
+        .expect("cannot create metric");
+        register(Box::new(errors.clone()));
+
+        let active_subscriptions = IntGauge::new(
+            "graphql_active_subscriptions",
+            "Number of the running subscriptions",
+        )
+        .expect("cannot create metric");
+        register(Box::new(active_subscriptions.clone()));
+
+        let pool_connections = IntGauge::new(
+            "sqlite_pool_connections",
+            "Number of the open connections of the pool, idle or in use",
+        )
+        .expect("cannot create metric");
+        register(Box::new(pool_connections.clone()));
+
+        let pool_idle_connections = IntGauge::new(
+            "sqlite_pool_idle_connections",
+            "Number of the idle connections of the pool",
+        )
+        .expect("cannot create metric");
+        register(Box::new(pool_idle_connections.clone()));
+
+        let pool_max_connections = IntGauge::new(
+            "sqlite_pool_max_connections",
+            "Maximum number of connections of the pool",
+        )
+        .expect("cannot create metric");
+        register(Box::new(pool_max_connections.clone()));
+
+        Self(Arc::new(MetricsInner {
+            registry,
+            operation_duration,
+            operation_names: Mutex::new(HashSet::new()),
+            field_duration,
+            errors,
+            active_subscriptions,
+            pool_connections,
+            pool_idle_connections,
+            pool_max_connections,
+        }))
+    }
+
+    /// The names after the first [`MAX_OPERATION_NAME_COUNT`] ones, and the names longer than
+    /// [`MAX_OPERATION_NAME_LENGTH`], are observed as [`OTHER_OPERATION_NAME`], so the clients
+    /// cannot create an unbounded number of series
+    pub fn observe_operation(
+        &self,
+        operation_type: &str,
+        operation_name: &str,
+        duration: Duration,
+    ) {
+        let operation_name = {
+            let mut operation_names = self.0.operation_names.lock();
+
+            if operation_names.contains(operation_name)
+                || (operation_name.len() <= MAX_OPERATION_NAME_LENGTH
+                    && operation_names.len() < MAX_OPERATION_NAME_COUNT
+                    && operation_names.insert(operation_name.to_owned()))
+            {
+                operation_name
+            } else {
+                OTHER_OPERATION_NAME
+            }
+        };
+
+        self.0
+            .operation_duration
+            .with_label_values(&[operation_type, operation_name])
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn observe_field(&self, parent_type: &str, field_name: &str, duration: Duration) {
+        self.0
+            .field_duration
+            .with_label_values(&[parent_type, field_name])
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Counts the errors by the `code` of their extensions
+    pub fn count_errors(&self, errors: &[ServerError]) {
+        for error in errors {
+            let code = match error
+                .extensions
+                .as_ref()
+                .and_then(|extensions| extensions.get("code"))
+            {
+                Some(Value::String(code)) => code.as_str(),
+                _ => UNSPECIFIED_ERROR_CODE,
+            };
+
+            self.0.errors.with_label_values(&[code]).inc();
+        }
+    }
+
+    /// Counts a running subscription until the returned guard is dropped
+    pub fn track_subscription(&self) -> SubscriptionGuard {
+        self.0.active_subscriptions.inc();
+
+        SubscriptionGuard {
+            metrics: self.clone(),
+        }
+    }
+
+    /// Encodes every metric in the Prometheus text format, the statistics of the connection pool
+    /// are read at the time of the call
+    pub fn encode(&self, database: &Database) -> Result<String, prometheus::Error> {
+        let connection_pool = database.connection_pool_ref();
+        self.0
+            .pool_connections
+            .set(i64::from(connection_pool.size()));
+        self.0
+            .pool_idle_connections
+            .set(connection_pool.num_idle() as i64);
+        self.0
+            .pool_max_connections
+            .set(i64::from(connection_pool.options().get_max_connections()));
+
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.0.registry.gather(), &mut buffer)?;
+
+        String::from_utf8(buffer).map_err(|e| prometheus::Error::Msg(e.to_string()))
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct SubscriptionGuard {
+    metrics: Metrics,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        self.metrics.0.active_subscriptions.dec();
+    }
+}
+
+/// Route of the Prometheus scraper, `/metrics`
+pub fn metrics_routes(state: State) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(state)
+}
+
+async fn metrics(AxumState(state): AxumState<State>) -> Response {
+    match state.metrics.encode(&state.database) {
+        Ok(body) => ([(CONTENT_TYPE, prometheus::TEXT_FORMAT)], body).into_response(),
+        Err(e) => {
+            log::error!(
+                "{}, cannot encode the metrics, error = {e}",
+                log_location!()
+            );
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
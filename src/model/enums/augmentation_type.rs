@@ -45,7 +45,15 @@ impl From<AugmentationType> for String {
     }
 }
 pub mod error {
+    use crate::error::{ErrorCode, INTERNAL_ERROR_CODE};
+
     #[derive(Clone, Debug, thiserror::Error)]
     #[error("InvalidAugmentationType: '{0}'")]
     pub struct InvalidAugmentationType(pub String);
+
+    impl ErrorCode for InvalidAugmentationType {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
 }
@@ -0,0 +1,13 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+/// Field of a character that a character list can be sorted by
+#[derive(Debug, Enum, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum CharacterOrderField {
+    Nickname,
+    /// Characters without a name are sorted as if their name was empty
+    Name,
+    /// Time the character was created
+    CreatedAt,
+    Race,
+}
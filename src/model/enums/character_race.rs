@@ -3,6 +3,8 @@ use std::str::FromStr;
 use async_graphql::Enum;
 use error::InvalidCharacterRace;
 
+use super::augmentation_type::AugmentationType;
+
 #[derive(Debug, Enum, Copy, Clone, Eq, PartialEq)]
 pub enum CharacterRace {
     Human,
@@ -10,6 +12,25 @@ pub enum CharacterRace {
     Cyborg,
 }
 
+impl CharacterRace {
+    /// Returns whether characters of the race can hold augmentations at all
+    pub fn is_augmentable(self) -> bool {
+        match self {
+            CharacterRace::Android | CharacterRace::Cyborg => true,
+            CharacterRace::Human => false,
+        }
+    }
+
+    /// Returns whether characters of the race can hold augmentations of the given type
+    pub fn supports_augmentation_type(self, augmentation_type: AugmentationType) -> bool {
+        match (self, augmentation_type) {
+            (CharacterRace::Human, _) => false,
+            (CharacterRace::Android, AugmentationType::GeneticModification) => false,
+            (CharacterRace::Android, _) | (CharacterRace::Cyborg, _) => true,
+        }
+    }
+}
+
 impl std::fmt::Display for CharacterRace {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", Into::<&str>::into(*self))
@@ -45,7 +66,15 @@ impl From<CharacterRace> for String {
     }
 }
 pub mod error {
+    use crate::error::{ErrorCode, INTERNAL_ERROR_CODE};
+
     #[derive(Clone, Debug, thiserror::Error)]
     #[error("InvalidCharacterRace: '{0}'")]
     pub struct InvalidCharacterRace(pub String);
+
+    impl ErrorCode for InvalidCharacterRace {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
 }
@@ -0,0 +1,11 @@
+use async_graphql::Enum;
+
+/// What to do with the augmentations that the new race of a character cannot hold
+#[derive(Debug, Enum, Copy, Clone, Default, Eq, PartialEq)]
+pub enum IncompatibleAugmentationPolicy {
+    /// Remove the incompatible augmentations
+    Drop,
+    /// Reject the conversion
+    #[default]
+    Fail,
+}
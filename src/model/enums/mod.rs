@@ -1,2 +1,6 @@
 pub mod augmentation_type;
+pub mod character_order_field;
 pub mod character_race;
+pub mod incompatible_augmentation_policy;
+pub mod order_direction;
+pub mod role;
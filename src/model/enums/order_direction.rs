@@ -0,0 +1,9 @@
+use async_graphql::Enum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Enum, Copy, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum OrderDirection {
+    #[default]
+    Asc,
+    Desc,
+}
@@ -0,0 +1,70 @@
+use std::str::FromStr;
+
+use async_graphql::Enum;
+use error::InvalidRole;
+
+#[derive(Debug, Enum, Copy, Clone, Eq, PartialEq)]
+pub enum Role {
+    Player,
+    Moderator,
+    Admin,
+}
+
+impl Role {
+    /// Returns whether holding `self` grants the permissions of `other`
+    pub fn includes(self, other: Role) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Moderator => matches!(other, Role::Moderator | Role::Player),
+            Role::Player => other == Role::Player,
+        }
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Into::<&str>::into(*self))
+    }
+}
+
+impl FromStr for Role {
+    type Err = InvalidRole;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Admin" => Ok(Role::Admin),
+            "Moderator" => Ok(Role::Moderator),
+            "Player" => Ok(Role::Player),
+            _ => Err(InvalidRole(s.into())),
+        }
+    }
+}
+
+impl From<Role> for &str {
+    fn from(value: Role) -> Self {
+        match value {
+            Role::Admin => "Admin",
+            Role::Moderator => "Moderator",
+            Role::Player => "Player",
+        }
+    }
+}
+
+impl From<Role> for String {
+    fn from(value: Role) -> Self {
+        <&str>::from(value).into()
+    }
+}
+pub mod error {
+    use crate::error::{ErrorCode, INTERNAL_ERROR_CODE};
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("InvalidRole: '{0}'")]
+    pub struct InvalidRole(pub String);
+
+    impl ErrorCode for InvalidRole {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
+}
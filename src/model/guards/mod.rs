@@ -0,0 +1 @@
+pub mod role_guard;
@@ -0,0 +1,74 @@
+use async_graphql::{Context, ErrorExtensions, Guard};
+
+use crate::{
+    auth::{error::Forbidden, ContextAuthExt},
+    model::{enums::role::Role, scalars::id::Id},
+    sql_queries::sql_user_role::get_user_roles,
+    state::State,
+};
+
+/// Allows access if the caller holds a role that includes `role`
+pub struct RoleGuard {
+    role: Role,
+}
+
+impl RoleGuard {
+    pub fn new(role: Role) -> Self {
+        Self { role }
+    }
+}
+
+impl Guard for RoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let user_id = &ctx.authenticated_user().map_err(|e| e.extend())?.user_id;
+
+        if caller_has_role(ctx, user_id, self.role).await? {
+            Ok(())
+        } else {
+            Err(Forbidden {
+                required_role: self.role,
+            }
+            .extend())
+        }
+    }
+}
+
+/// Allows access if the caller is the owner of the resource or holds a role that includes `role`
+pub struct OwnerOrRoleGuard {
+    owner_id: Id,
+    role: Role,
+}
+
+impl OwnerOrRoleGuard {
+    pub fn new(owner_id: Id, role: Role) -> Self {
+        Self { owner_id, role }
+    }
+}
+
+impl Guard for OwnerOrRoleGuard {
+    async fn check(&self, ctx: &Context<'_>) -> async_graphql::Result<()> {
+        let user_id = &ctx.authenticated_user().map_err(|e| e.extend())?.user_id;
+
+        if *user_id == self.owner_id || caller_has_role(ctx, user_id, self.role).await? {
+            Ok(())
+        } else {
+            Err(Forbidden {
+                required_role: self.role,
+            }
+            .extend())
+        }
+    }
+}
+
+async fn caller_has_role(
+    ctx: &Context<'_>,
+    user_id: &Id,
+    role: Role,
+) -> async_graphql::Result<bool> {
+    let state = ctx.data::<State>()?;
+    let roles = get_user_roles(state, user_id)
+        .await
+        .map_err(|e| e.extend())?;
+
+    Ok(roles.iter().any(|caller_role| caller_role.includes(role)))
+}
@@ -0,0 +1,10 @@
+use async_graphql::InputObject;
+
+use crate::model::enums::augmentation_type::AugmentationType;
+
+#[derive(InputObject)]
+pub struct AugmentationInput {
+    pub name: String,
+    pub description: String,
+    pub r#type: AugmentationType,
+}
@@ -0,0 +1,16 @@
+use async_graphql::InputObject;
+
+use crate::model::enums::{augmentation_type::AugmentationType, character_race::CharacterRace};
+
+/// Conditions a character has to meet to be listed, absent fields are not checked
+#[derive(InputObject, Default)]
+pub struct CharacterFilter {
+    pub race_in: Option<Vec<CharacterRace>>,
+    /// Case-insensitive for ASCII letters
+    pub nickname_starts_with: Option<String>,
+    /// Case-insensitive for ASCII letters
+    pub nickname_contains: Option<String>,
+    pub has_name: Option<bool>,
+    /// The character holds at least one augmentation of the type
+    pub has_augmentation_type: Option<AugmentationType>,
+}
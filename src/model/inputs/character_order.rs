@@ -0,0 +1,13 @@
+use async_graphql::InputObject;
+
+use crate::model::enums::{
+    character_order_field::CharacterOrderField, order_direction::OrderDirection,
+};
+
+/// Sort key of a character list, characters that are equal by every key are ordered by their ids
+#[derive(InputObject, Clone, Copy)]
+pub struct CharacterOrder {
+    pub field: CharacterOrderField,
+    #[graphql(default)]
+    pub direction: OrderDirection,
+}
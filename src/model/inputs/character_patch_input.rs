@@ -0,0 +1,9 @@
+use async_graphql::{InputObject, MaybeUndefined};
+
+/// Changes to apply to a character, absent fields are left untouched
+#[derive(InputObject)]
+pub struct CharacterPatchInput {
+    pub nickname: Option<String>,
+    /// Set to `null` to clear the name
+    pub name: MaybeUndefined<String>,
+}
@@ -1 +1,5 @@
+pub mod augmentation_input;
 pub mod character_creation_input;
+pub mod character_filter;
+pub mod character_order;
+pub mod character_patch_input;
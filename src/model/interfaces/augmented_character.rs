@@ -5,13 +5,13 @@ use async_graphql::Interface;
 
 use crate::model::{
     enums::{augmentation_type::AugmentationType, character_race::CharacterRace},
-    scalars::id::Id,
+    scalars::character_id::CharacterId,
     types::{android::Android, augmentation::Augmentation, cyborg::Cyborg},
 };
 
-#[derive(Interface)]
+#[derive(Clone, Interface)]
 #[graphql(
-    field(name = "id", ty = "&Id"),
+    field(name = "id", ty = "CharacterId"),
     field(
         name = "name",
         ty = "&Option<String>",
@@ -22,7 +22,7 @@ use crate::model::{
     field(
         name = "augmentations",
         ty = "Vec<Augmentation>",
-        arg(name = "type", ty = "AugmentationType"),
+        arg(name = "type", ty = "Option<AugmentationType>"),
     ),
     field(
         name = "augmentations_by_type",
@@ -5,15 +5,15 @@ use async_graphql::Interface;
 
 use crate::model::{
     enums::character_race::CharacterRace,
-    scalars::id::Id,
+    scalars::character_id::CharacterId,
     types::{android::Android, cyborg::Cyborg, human::Human},
 };
 
 use super::augmented_character::AugmentedCharacter;
 
-#[derive(Interface)]
+#[derive(Clone, Interface)]
 #[graphql(
-    field(name = "id", ty = "&Id", desc = "Id of the character"),
+    field(name = "id", ty = "CharacterId", desc = "Id of the character"),
     field(
         name = "name",
         ty = "&Option<String>",
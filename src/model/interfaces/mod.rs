@@ -1,2 +1,3 @@
 pub mod augmented_character;
 pub mod character;
+pub mod node;
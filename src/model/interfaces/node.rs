@@ -0,0 +1,35 @@
+#![allow(clippy::duplicated_attributes)]
+
+use async_graphql::Interface;
+
+use crate::model::{
+    scalars::node_id::NodeId,
+    types::{
+        android::Android, augmentation::Augmentation, cyborg::Cyborg, human::Human, user::User,
+    },
+};
+
+use super::{augmented_character::AugmentedCharacter, character::Character};
+
+/// Object that can be refetched by its id with `Query.node`
+#[derive(Clone, Interface)]
+#[graphql(field(name = "id", ty = "NodeId", desc = "Globally unique id of the object"))]
+pub enum Node {
+    User(User),
+    Android(Android),
+    Cyborg(Cyborg),
+    Human(Human),
+    Augmentation(Augmentation),
+}
+
+impl From<Character> for Node {
+    fn from(value: Character) -> Self {
+        match value {
+            Character::AugmentedCharacter(AugmentedCharacter::Android(android))
+            | Character::Android(android) => Node::Android(android),
+            Character::AugmentedCharacter(AugmentedCharacter::Cyborg(cyborg))
+            | Character::Cyborg(cyborg) => Node::Cyborg(cyborg),
+            Character::Human(human) => Node::Human(human),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+
+use crate::{
+    model::scalars::id::Id,
+    sql_queries::sql_augmentation::{
+        error::GetAugmentationsError, get_augmentations_by_ids, OwnedAugmentation,
+    },
+    state::State,
+};
+
+/// Loads augmentations by id regardless of their owner, callers have to check
+/// [`OwnedAugmentation::user_id`]
+pub struct AugmentationLoader {
+    state: State,
+}
+
+impl AugmentationLoader {
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Id> for AugmentationLoader {
+    type Value = OwnedAugmentation;
+    type Error = GetAugmentationsError;
+
+    async fn load(&self, keys: &[Id]) -> Result<HashMap<Id, Self::Value>, Self::Error> {
+        get_augmentations_by_ids(&self.state, keys).await
+    }
+}
@@ -0,0 +1,31 @@
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+
+use crate::{
+    model::{scalars::id::Id, types::augmentation::Augmentation},
+    sql_queries::sql_augmentation::{
+        error::GetAugmentationsError, get_augmentations_by_character_ids,
+    },
+    state::State,
+};
+
+/// Loads every augmentation of characters, keyed by the id of the character
+pub struct AugmentationsByCharacterLoader {
+    state: State,
+}
+
+impl AugmentationsByCharacterLoader {
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Id> for AugmentationsByCharacterLoader {
+    type Value = Vec<Augmentation>;
+    type Error = GetAugmentationsError;
+
+    async fn load(&self, keys: &[Id]) -> Result<HashMap<Id, Self::Value>, Self::Error> {
+        get_augmentations_by_character_ids(&self.state, keys).await
+    }
+}
@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+
+use crate::{
+    model::scalars::id::Id,
+    sql_queries::sql_character::{
+        error::GetCharactersError, get_characters_by_ids, OwnedCharacter,
+    },
+    state::State,
+};
+
+/// Loads characters by id regardless of their owner, callers have to check
+/// [`OwnedCharacter::user_id`]
+pub struct CharacterLoader {
+    state: State,
+}
+
+impl CharacterLoader {
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Id> for CharacterLoader {
+    type Value = OwnedCharacter;
+    type Error = GetCharactersError;
+
+    async fn load(&self, keys: &[Id]) -> Result<HashMap<Id, Self::Value>, Self::Error> {
+        get_characters_by_ids(&self.state, keys).await
+    }
+}
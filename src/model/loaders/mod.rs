@@ -0,0 +1,106 @@
+pub mod augmentation_loader;
+pub mod augmentations_by_character_loader;
+pub mod character_loader;
+pub mod user_loader;
+
+use std::sync::Arc;
+
+use async_graphql::{
+    dataloader::{DataLoader, HashMapCache},
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest},
+    Context, Request, ServerResult,
+};
+use augmentation_loader::AugmentationLoader;
+use augmentations_by_character_loader::AugmentationsByCharacterLoader;
+use character_loader::CharacterLoader;
+use user_loader::UserLoader;
+
+use crate::state::State;
+
+/// Adds a new set of data loaders to every request, so the cached values live only as long as
+/// the request
+pub struct DataLoaders {
+    state: State,
+}
+
+impl DataLoaders {
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+}
+
+impl ExtensionFactory for DataLoaders {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(DataLoadersExtension {
+            state: self.state.clone(),
+        })
+    }
+}
+
+struct DataLoadersExtension {
+    state: State,
+}
+
+#[async_trait::async_trait]
+impl Extension for DataLoadersExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let request = request
+            .data(DataLoader::with_cache(
+                UserLoader::new(self.state.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            ))
+            .data(DataLoader::with_cache(
+                CharacterLoader::new(self.state.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            ))
+            .data(DataLoader::with_cache(
+                AugmentationLoader::new(self.state.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            ))
+            .data(DataLoader::with_cache(
+                AugmentationsByCharacterLoader::new(self.state.clone()),
+                tokio::spawn,
+                HashMapCache::default(),
+            ));
+
+        next.run(ctx, request).await
+    }
+}
+
+/// Accessors of the data loaders added to every request by [`DataLoaders`]
+pub trait ContextDataLoadersExt {
+    fn user_loader(&self) -> &DataLoader<UserLoader, HashMapCache>;
+    fn character_loader(&self) -> &DataLoader<CharacterLoader, HashMapCache>;
+    fn augmentation_loader(&self) -> &DataLoader<AugmentationLoader, HashMapCache>;
+    fn augmentations_by_character_loader(
+        &self,
+    ) -> &DataLoader<AugmentationsByCharacterLoader, HashMapCache>;
+}
+
+impl ContextDataLoadersExt for Context<'_> {
+    fn user_loader(&self) -> &DataLoader<UserLoader, HashMapCache> {
+        self.data_unchecked()
+    }
+
+    fn character_loader(&self) -> &DataLoader<CharacterLoader, HashMapCache> {
+        self.data_unchecked()
+    }
+
+    fn augmentation_loader(&self) -> &DataLoader<AugmentationLoader, HashMapCache> {
+        self.data_unchecked()
+    }
+
+    fn augmentations_by_character_loader(
+        &self,
+    ) -> &DataLoader<AugmentationsByCharacterLoader, HashMapCache> {
+        self.data_unchecked()
+    }
+}
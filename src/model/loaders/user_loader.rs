@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+use async_graphql::dataloader::Loader;
+
+use crate::{
+    model::{scalars::id::Id, types::user::User},
+    sql_queries::sql_user::{error::GetUsersByIdsError, get_users_by_ids},
+    state::State,
+};
+
+pub struct UserLoader {
+    state: State,
+}
+
+impl UserLoader {
+    pub fn new(state: State) -> Self {
+        Self { state }
+    }
+}
+
+impl Loader<Id> for UserLoader {
+    type Value = User;
+    type Error = GetUsersByIdsError;
+
+    async fn load(&self, keys: &[Id]) -> Result<HashMap<Id, Self::Value>, Self::Error> {
+        Ok(get_users_by_ids(&self.state, keys)
+            .await?
+            .into_iter()
+            .map(|user| (user.id.clone(), user))
+            .collect())
+    }
+}
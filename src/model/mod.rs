@@ -1,8 +1,12 @@
 pub mod enums;
+pub mod guards;
 pub mod inputs;
 pub mod interfaces;
+pub mod loaders;
 pub mod mutation;
 pub mod query;
 pub mod scalars;
 pub mod subscription;
 pub mod types;
+pub mod unions;
+pub mod validators;
@@ -1,30 +1,591 @@
-use async_graphql::Object;
+use async_graphql::{Context, ErrorExtensions, Object};
+use error::{
+    MutationConvertCharacterRaceError, MutationCreateCharacterError, MutationDeleteCharacterError,
+    MutationInstallAugmentationError, MutationRemoveAugmentationError,
+    MutationReplaceAugmentationError, MutationSetDisplayNameError, MutationUpdateCharacterError,
+};
 
-use crate::{error::UnimplementedError, state::State};
+use crate::{
+    auth::ContextAuthExt,
+    sql_queries::{
+        sql_augmentation::{install_augmentation, remove_augmentation, replace_augmentation},
+        sql_character::{
+            convert_character_race, create_character, delete_character, update_character,
+        },
+        sql_user::set_display_name,
+        sql_user_role::{grant_role, revoke_role},
+    },
+    state::State,
+};
 
 use super::{
-    inputs::character_creation_input::CharacterCreationInput, interfaces::character::Character,
-    scalars::id::Id, types::user::User,
+    enums::{
+        character_race::CharacterRace,
+        incompatible_augmentation_policy::IncompatibleAugmentationPolicy, role::Role,
+    },
+    guards::role_guard::RoleGuard,
+    inputs::{
+        augmentation_input::AugmentationInput, character_creation_input::CharacterCreationInput,
+        character_patch_input::CharacterPatchInput,
+    },
+    interfaces::character::Character,
+    scalars::{augmentation_id::AugmentationId, character_id::CharacterId, user_id::UserId},
+    types::{augmentation::Augmentation, user::User},
+    validators::display_name_validator::DisplayNameValidator,
 };
 
 pub struct Mutation {
-    pub _state: State,
+    pub state: State,
 }
 
 #[Object]
 impl Mutation {
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
     pub async fn set_display_name(
         &self,
-        _display_name: String,
-    ) -> Result<User, UnimplementedError> {
-        Err(UnimplementedError("Mutation::set_display_name".into()))
+        ctx: &Context<'_>,
+        #[graphql(validator(custom = "DisplayNameValidator"))] display_name: String,
+    ) -> async_graphql::Result<User> {
+        self.resolve_set_display_name(ctx, display_name)
+            .await
+            .map_err(|e| e.extend())
     }
 
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
     pub async fn create_character(
         &self,
-        _user_id: Id,
-        _character_definition: CharacterCreationInput,
-    ) -> Result<Character, UnimplementedError> {
-        Err(UnimplementedError("Mutation::create_character".into()))
+        ctx: &Context<'_>,
+        character_definition: CharacterCreationInput,
+    ) -> async_graphql::Result<Character> {
+        self.resolve_create_character(ctx, character_definition)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn update_character(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+        patch: CharacterPatchInput,
+    ) -> async_graphql::Result<Character> {
+        self.resolve_update_character(ctx, id, patch)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Deletes the character together with its augmentations and returns its id
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn delete_character(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+    ) -> async_graphql::Result<CharacterId> {
+        self.resolve_delete_character(ctx, id)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Changes the race of the character, augmentations that the new race cannot hold are handled
+    /// according to `incompatibleAugmentations`
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn convert_character_race(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+        new_race: CharacterRace,
+        #[graphql(default)] incompatible_augmentations: IncompatibleAugmentationPolicy,
+    ) -> async_graphql::Result<Character> {
+        self.resolve_convert_character_race(ctx, id, new_race, incompatible_augmentations)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn install_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        character_id: CharacterId,
+        augmentation_definition: AugmentationInput,
+    ) -> async_graphql::Result<Augmentation> {
+        self.resolve_install_augmentation(ctx, character_id, augmentation_definition)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Removes the augmentation and returns it
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn remove_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        id: AugmentationId,
+    ) -> async_graphql::Result<Augmentation> {
+        self.resolve_remove_augmentation(ctx, id)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Replaces the augmentation with a new one on the same character and returns the new one
+    #[graphql(guard = "RoleGuard::new(Role::Player)")]
+    pub async fn replace_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        id: AugmentationId,
+        augmentation_definition: AugmentationInput,
+    ) -> async_graphql::Result<Augmentation> {
+        self.resolve_replace_augmentation(ctx, id, augmentation_definition)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Role::Admin)")]
+    pub async fn grant_role(
+        &self,
+        user_id: UserId,
+        role: Role,
+    ) -> async_graphql::Result<Vec<Role>> {
+        grant_role(&self.state, user_id.as_id(), role)
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Revokes a role granted to the user, every user stays a `Player`
+    #[graphql(guard = "RoleGuard::new(Role::Admin)")]
+    pub async fn revoke_role(
+        &self,
+        user_id: UserId,
+        role: Role,
+    ) -> async_graphql::Result<Vec<Role>> {
+        revoke_role(&self.state, user_id.as_id(), role)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+impl Mutation {
+    async fn resolve_set_display_name(
+        &self,
+        ctx: &Context<'_>,
+        display_name: String,
+    ) -> Result<User, MutationSetDisplayNameError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(set_display_name(&self.state, user_id.clone(), &display_name).await?)
+    }
+
+    async fn resolve_create_character(
+        &self,
+        ctx: &Context<'_>,
+        character_definition: CharacterCreationInput,
+    ) -> Result<Character, MutationCreateCharacterError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(create_character(&self.state, user_id, &character_definition).await?)
+    }
+
+    async fn resolve_update_character(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+        patch: CharacterPatchInput,
+    ) -> Result<Character, MutationUpdateCharacterError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(update_character(&self.state, user_id, id.as_id(), &patch).await?)
+    }
+
+    async fn resolve_delete_character(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+    ) -> Result<CharacterId, MutationDeleteCharacterError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(CharacterId(
+            delete_character(&self.state, user_id, id.as_id()).await?,
+        ))
+    }
+
+    async fn resolve_convert_character_race(
+        &self,
+        ctx: &Context<'_>,
+        id: CharacterId,
+        new_race: CharacterRace,
+        incompatible_augmentations: IncompatibleAugmentationPolicy,
+    ) -> Result<Character, MutationConvertCharacterRaceError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(convert_character_race(
+            &self.state,
+            user_id,
+            id.as_id(),
+            new_race,
+            incompatible_augmentations,
+        )
+        .await?)
+    }
+
+    async fn resolve_install_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        character_id: CharacterId,
+        augmentation_definition: AugmentationInput,
+    ) -> Result<Augmentation, MutationInstallAugmentationError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(install_augmentation(
+            &self.state,
+            user_id,
+            character_id.as_id(),
+            &augmentation_definition,
+        )
+        .await?)
+    }
+
+    async fn resolve_remove_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        id: AugmentationId,
+    ) -> Result<Augmentation, MutationRemoveAugmentationError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(remove_augmentation(&self.state, user_id, id.as_id()).await?)
+    }
+
+    async fn resolve_replace_augmentation(
+        &self,
+        ctx: &Context<'_>,
+        id: AugmentationId,
+        augmentation_definition: AugmentationInput,
+    ) -> Result<Augmentation, MutationReplaceAugmentationError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(
+            replace_augmentation(&self.state, user_id, id.as_id(), &augmentation_definition)
+                .await?,
+        )
+    }
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::{
+        auth::error::Unauthenticated,
+        error::ErrorCode,
+        sql_queries::{
+            sql_augmentation::error::{
+                InstallAugmentationError, RemoveAugmentationError, ReplaceAugmentationError,
+            },
+            sql_character::error::{
+                ConvertCharacterRaceError, CreateCharacterError, DeleteCharacterError,
+                UpdateCharacterError,
+            },
+            sql_user::error::SetDisplayNameError,
+        },
+    };
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationSetDisplayNameError {
+        #[error("MutationSetDisplayNameError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationSetDisplayNameError: '{0}'")]
+        SetDisplayNameError(
+            #[from]
+            #[source]
+            SetDisplayNameError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationCreateCharacterError {
+        #[error("MutationCreateCharacterError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationCreateCharacterError: '{0}'")]
+        CreateCharacterError(
+            #[from]
+            #[source]
+            CreateCharacterError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationUpdateCharacterError {
+        #[error("MutationUpdateCharacterError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationUpdateCharacterError: '{0}'")]
+        UpdateCharacterError(
+            #[from]
+            #[source]
+            UpdateCharacterError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationDeleteCharacterError {
+        #[error("MutationDeleteCharacterError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationDeleteCharacterError: '{0}'")]
+        DeleteCharacterError(
+            #[from]
+            #[source]
+            DeleteCharacterError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationConvertCharacterRaceError {
+        #[error("MutationConvertCharacterRaceError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationConvertCharacterRaceError: '{0}'")]
+        ConvertCharacterRaceError(
+            #[from]
+            #[source]
+            ConvertCharacterRaceError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationInstallAugmentationError {
+        #[error("MutationInstallAugmentationError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationInstallAugmentationError: '{0}'")]
+        InstallAugmentationError(
+            #[from]
+            #[source]
+            InstallAugmentationError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationRemoveAugmentationError {
+        #[error("MutationRemoveAugmentationError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationRemoveAugmentationError: '{0}'")]
+        RemoveAugmentationError(
+            #[from]
+            #[source]
+            RemoveAugmentationError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum MutationReplaceAugmentationError {
+        #[error("MutationReplaceAugmentationError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("MutationReplaceAugmentationError: '{0}'")]
+        ReplaceAugmentationError(
+            #[from]
+            #[source]
+            ReplaceAugmentationError,
+        ),
+    }
+
+    impl ErrorCode for MutationSetDisplayNameError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::SetDisplayNameError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::SetDisplayNameError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationSetDisplayNameError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationCreateCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::CreateCharacterError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::CreateCharacterError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationCreateCharacterError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationUpdateCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::UpdateCharacterError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::UpdateCharacterError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationUpdateCharacterError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationDeleteCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::DeleteCharacterError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::DeleteCharacterError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationDeleteCharacterError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationConvertCharacterRaceError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::ConvertCharacterRaceError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::ConvertCharacterRaceError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationConvertCharacterRaceError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationInstallAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::InstallAugmentationError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::InstallAugmentationError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationInstallAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationRemoveAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::RemoveAugmentationError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::RemoveAugmentationError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationRemoveAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for MutationReplaceAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::ReplaceAugmentationError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::ReplaceAugmentationError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for MutationReplaceAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
     }
 }
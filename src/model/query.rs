@@ -1,13 +1,27 @@
-use async_graphql::Object;
-use error::QueryMeError;
+use async_graphql::{Context, ErrorExtensions, Object};
+use error::{QueryMeError, QueryNodesError, QuerySearchError};
 
 use crate::{
+    auth::ContextAuthExt,
     log_location,
-    sql_queries::sql_user::{error::CannotFindUserById, get_user_by_id},
+    sql_queries::{
+        sql_id::{get_entity_types, EntityType},
+        sql_search::search,
+        sql_user::error::CannotFindUserById,
+        sql_user_role::get_user_roles,
+    },
     state::State,
 };
 
-use super::{scalars::id::Id, types::user::User};
+use super::{
+    enums::role::Role,
+    guards::role_guard::RoleGuard,
+    interfaces::node::Node,
+    loaders::ContextDataLoadersExt,
+    scalars::{id::Id, user_id::UserId},
+    types::user::User,
+    unions::search_hit::SearchHit,
+};
 
 pub struct Query {
     pub state: State,
@@ -15,27 +29,173 @@ pub struct Query {
 
 #[Object]
 impl Query {
-    pub async fn me(&self) -> Result<Option<User>, QueryMeError> {
-        let user_id: Id = "e30ba9c8-03bf-4ae8-af35-e8366a8fe160".into();
+    pub async fn me(&self, ctx: &Context<'_>) -> async_graphql::Result<Option<User>> {
+        self.resolve_me(ctx).await.map_err(|e| e.extend())
+    }
+
+    #[graphql(guard = "RoleGuard::new(Role::Moderator)")]
+    pub async fn user_by_id(
+        &self,
+        ctx: &Context<'_>,
+        id: UserId,
+    ) -> async_graphql::Result<Option<User>> {
+        ctx.user_loader()
+            .load_one(id.into())
+            .await
+            .map_err(|e| e.extend())
+    }
+
+    /// Fetches any object by its id
+    ///
+    /// Returns `null` when the id is unknown or the caller is not allowed to see the object, users
+    /// are visible to themselves and to moderators, characters and augmentations to their owners
+    /// and to admins.
+    pub async fn node(&self, ctx: &Context<'_>, id: Id) -> async_graphql::Result<Option<Node>> {
+        Ok(self
+            .resolve_nodes(ctx, vec![id])
+            .await
+            .map_err(|e| e.extend())?
+            .pop()
+            .flatten())
+    }
+
+    /// Fetches objects by their ids, the result has an entry for every id in the same order
+    ///
+    /// The entries follow the rules of `node`.
+    pub async fn nodes(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<Id>,
+    ) -> async_graphql::Result<Vec<Option<Node>>> {
+        self.resolve_nodes(ctx, ids).await.map_err(|e| e.extend())
+    }
+
+    /// Searches the nicknames and names of the characters of the caller, and the names and
+    /// descriptions of their augmentations
+    ///
+    /// Every word of the query has to match the beginning of a word of the hit, the best hits come
+    /// first. At most `first` hits are returned, 20 when it is not given and 100 at most.
+    pub async fn search(
+        &self,
+        ctx: &Context<'_>,
+        query: String,
+        first: Option<usize>,
+    ) -> async_graphql::Result<Vec<SearchHit>> {
+        self.resolve_search(ctx, &query, first)
+            .await
+            .map_err(|e| e.extend())
+    }
+}
+
+impl Query {
+    async fn resolve_me(&self, ctx: &Context<'_>) -> Result<Option<User>, QueryMeError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
         Ok(Some(
-            get_user_by_id(&self.state, user_id.clone())
+            ctx.user_loader()
+                .load_one(user_id.clone())
                 .await?
                 .ok_or_else(|| CannotFindUserById(user_id.clone()))
                 .inspect_err(|e| log::error!("{}, {e}", log_location!()))?,
         ))
     }
+
+    async fn resolve_nodes(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<Id>,
+    ) -> Result<Vec<Option<Node>>, QueryNodesError> {
+        let caller_id = &ctx.authenticated_user()?.user_id;
+        let caller_roles = get_user_roles(&self.state, caller_id).await?;
+        let caller_has_role = |role| {
+            caller_roles
+                .iter()
+                .any(|caller_role| caller_role.includes(role))
+        };
+
+        let entity_types = get_entity_types(&self.state, &ids).await?;
+        let ids_of_type = |entity_type| {
+            entity_types
+                .iter()
+                .filter(move |(_, id_entity_type)| **id_entity_type == entity_type)
+                .map(|(id, _)| id.clone())
+        };
+
+        let mut users = ctx
+            .user_loader()
+            .load_many(ids_of_type(EntityType::User))
+            .await?;
+        let mut characters = ctx
+            .character_loader()
+            .load_many(ids_of_type(EntityType::Character))
+            .await?;
+        let mut augmentations = ctx
+            .augmentation_loader()
+            .load_many(ids_of_type(EntityType::Augmentation))
+            .await?;
+
+        let can_see_users = caller_has_role(Role::Moderator);
+        let can_see_characters = caller_has_role(Role::Admin);
+
+        Ok(ids
+            .iter()
+            .map(|id| match entity_types.get(id)? {
+                EntityType::User => users
+                    .remove(id)
+                    .filter(|user| can_see_users || user.id == *caller_id)
+                    .map(Node::User),
+                EntityType::Character => characters
+                    .remove(id)
+                    .filter(|character| can_see_characters || character.user_id == *caller_id)
+                    .map(|character| character.character.into()),
+                EntityType::Augmentation => augmentations
+                    .remove(id)
+                    .filter(|augmentation| can_see_characters || augmentation.user_id == *caller_id)
+                    .map(|augmentation| Node::Augmentation(augmentation.augmentation)),
+            })
+            .collect())
+    }
+
+    async fn resolve_search(
+        &self,
+        ctx: &Context<'_>,
+        query: &str,
+        first: Option<usize>,
+    ) -> Result<Vec<SearchHit>, QuerySearchError> {
+        let user_id = &ctx.authenticated_user()?.user_id;
+        Ok(search(&self.state, user_id, query, first).await?)
+    }
 }
 
 pub mod error {
-    use crate::sql_queries::sql_user::error::{CannotFindUserById, GetUserByIdError};
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::{
+        auth::error::Unauthenticated,
+        error::ErrorCode,
+        sql_queries::{
+            sql_augmentation::error::GetAugmentationsError,
+            sql_character::error::GetCharactersError,
+            sql_id::error::GetEntityTypesError,
+            sql_search::error::SearchError,
+            sql_user::error::{CannotFindUserById, GetUsersByIdsError},
+            sql_user_role::error::GetUserRolesError,
+        },
+    };
 
     #[derive(Debug, thiserror::Error)]
     pub enum QueryMeError {
         #[error("QueryMeError: '{0}'")]
-        GetUserByIdError(
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("QueryMeError: '{0}'")]
+        GetUsersByIdsError(
             #[from]
             #[source]
-            GetUserByIdError,
+            GetUsersByIdsError,
         ),
 
         #[error("QueryMeError: '{0}'")]
@@ -45,4 +205,142 @@ pub mod error {
             CannotFindUserById,
         ),
     }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum QueryNodesError {
+        #[error("QueryNodesError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("QueryNodesError: '{0}'")]
+        GetUserRolesError(
+            #[from]
+            #[source]
+            GetUserRolesError,
+        ),
+
+        #[error("QueryNodesError: '{0}'")]
+        GetEntityTypesError(
+            #[from]
+            #[source]
+            GetEntityTypesError,
+        ),
+
+        #[error("QueryNodesError: '{0}'")]
+        GetUsersByIdsError(
+            #[from]
+            #[source]
+            GetUsersByIdsError,
+        ),
+
+        #[error("QueryNodesError: '{0}'")]
+        GetCharactersError(
+            #[from]
+            #[source]
+            GetCharactersError,
+        ),
+
+        #[error("QueryNodesError: '{0}'")]
+        GetAugmentationsError(
+            #[from]
+            #[source]
+            GetAugmentationsError,
+        ),
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    pub enum QuerySearchError {
+        #[error("QuerySearchError: '{0}'")]
+        Unauthenticated(
+            #[from]
+            #[source]
+            Unauthenticated,
+        ),
+
+        #[error("QuerySearchError: '{0}'")]
+        SearchError(
+            #[from]
+            #[source]
+            SearchError,
+        ),
+    }
+
+    impl ErrorCode for QueryMeError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::GetUsersByIdsError(e) => e.code(),
+                Self::CannotFindUserById(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::GetUsersByIdsError(e) => e.fields(),
+                Self::CannotFindUserById(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for QueryMeError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for QuerySearchError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::SearchError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::SearchError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for QuerySearchError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for QueryNodesError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::Unauthenticated(e) => e.code(),
+                Self::GetUserRolesError(e) => e.code(),
+                Self::GetEntityTypesError(e) => e.code(),
+                Self::GetUsersByIdsError(e) => e.code(),
+                Self::GetCharactersError(e) => e.code(),
+                Self::GetAugmentationsError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::Unauthenticated(e) => e.fields(),
+                Self::GetUserRolesError(e) => e.fields(),
+                Self::GetEntityTypesError(e) => e.fields(),
+                Self::GetUsersByIdsError(e) => e.fields(),
+                Self::GetCharactersError(e) => e.fields(),
+                Self::GetAugmentationsError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for QueryNodesError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
 }
@@ -0,0 +1,34 @@
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use super::id::{Id, AUGMENTATION_TAG};
+
+/// Id of an augmentation, sent to the clients as `Augmentation:<uuid>`
+///
+/// Ids tagged with another type are rejected when they are parsed, bare UUIDs are accepted as well.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AugmentationId(pub Id);
+
+impl AugmentationId {
+    pub fn as_id(&self) -> &Id {
+        &self.0
+    }
+}
+
+impl From<AugmentationId> for Id {
+    fn from(value: AugmentationId) -> Self {
+        value.0
+    }
+}
+
+#[Scalar(name = "ID")]
+impl ScalarType for AugmentationId {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        Id::parse_input(value, Some(AUGMENTATION_TAG))
+            .map(Self)
+            .map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        self.0.to_tagged_value(AUGMENTATION_TAG)
+    }
+}
@@ -0,0 +1,34 @@
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use super::id::{Id, CHARACTER_TAG};
+
+/// Id of a character, sent to the clients as `Character:<uuid>`
+///
+/// Ids tagged with another type are rejected when they are parsed, bare UUIDs are accepted as well.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct CharacterId(pub Id);
+
+impl CharacterId {
+    pub fn as_id(&self) -> &Id {
+        &self.0
+    }
+}
+
+impl From<CharacterId> for Id {
+    fn from(value: CharacterId) -> Self {
+        value.0
+    }
+}
+
+#[Scalar(name = "ID")]
+impl ScalarType for CharacterId {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        Id::parse_input(value, Some(CHARACTER_TAG))
+            .map(Self)
+            .map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        self.0.to_tagged_value(CHARACTER_TAG)
+    }
+}
@@ -1,12 +1,65 @@
 use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
 
-#[derive(Debug, Clone)]
+/// Separates the type tag from the UUID in tagged ids like `Character:<uuid>`
+const TAG_SEPARATOR: char = ':';
+
+pub(super) const USER_TAG: &str = "User";
+pub(super) const CHARACTER_TAG: &str = "Character";
+pub(super) const AUGMENTATION_TAG: &str = "Augmentation";
+
+const KNOWN_TAGS: [&str; 3] = [USER_TAG, CHARACTER_TAG, AUGMENTATION_TAG];
+
+/// Id of an entity, a UUID in its lowercase hyphenated form
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Id(pub String);
 
 impl Id {
     pub fn as_string_ref(&self) -> &String {
         &self.0
     }
+
+    /// Parses an id received from a client
+    ///
+    /// The id is either a bare UUID or a UUID tagged with the type of its entity, like
+    /// `Character:<uuid>`. When `expected_tag` is given, ids tagged with a different type are
+    /// rejected.
+    pub(super) fn parse_input(
+        value: Value,
+        expected_tag: Option<&'static str>,
+    ) -> Result<Self, String> {
+        let Value::String(value) = value else {
+            return Err(format!("expected an id, found '{value}'"));
+        };
+
+        let uuid = match value.split_once(TAG_SEPARATOR) {
+            Some((tag, uuid)) => {
+                if !KNOWN_TAGS.contains(&tag) {
+                    return Err(format!("unknown type '{tag}' in id '{value}'"));
+                }
+
+                if let Some(expected_tag) = expected_tag {
+                    if tag != expected_tag {
+                        return Err(format!(
+                            "expected an id tagged '{expected_tag}', found an id tagged '{tag}': '{value}'"
+                        ));
+                    }
+                }
+
+                uuid
+            }
+            None => value.as_str(),
+        };
+
+        let uuid = uuid::Uuid::parse_str(uuid)
+            .map_err(|e| format!("invalid id '{value}', the id has to be a UUID: {e}"))?;
+
+        Ok(Self(uuid.hyphenated().to_string()))
+    }
+
+    /// Formats the id for the clients tagged with the type of its entity
+    pub(super) fn to_tagged_value(&self, tag: &'static str) -> Value {
+        Value::String(format!("{tag}{TAG_SEPARATOR}{}", self.0))
+    }
 }
 
 impl From<String> for Id {
@@ -21,13 +74,11 @@ impl From<&str> for Id {
     }
 }
 
+/// Accepts the ids of every entity type, tagged or not
 #[Scalar(name = "ID")]
 impl ScalarType for Id {
     fn parse(value: Value) -> InputValueResult<Self> {
-        match value {
-            Value::String(val) => Ok(val.into()),
-            _ => Err(InputValueError::expected_type(value)),
-        }
+        Self::parse_input(value, None).map_err(InputValueError::custom)
     }
 
     fn to_value(&self) -> Value {
@@ -1 +1,5 @@
+pub mod augmentation_id;
+pub mod character_id;
 pub mod id;
+pub mod node_id;
+pub mod user_id;
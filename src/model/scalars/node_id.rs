@@ -0,0 +1,58 @@
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use super::{
+    augmentation_id::AugmentationId,
+    character_id::CharacterId,
+    id::{Id, AUGMENTATION_TAG, CHARACTER_TAG, USER_TAG},
+    user_id::UserId,
+};
+
+/// Id of any object implementing `Node`, formatted the same way as the id field of the object
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct NodeId {
+    tag: Option<&'static str>,
+    id: Id,
+}
+
+impl From<UserId> for NodeId {
+    fn from(value: UserId) -> Self {
+        Self {
+            tag: Some(USER_TAG),
+            id: value.into(),
+        }
+    }
+}
+
+impl From<CharacterId> for NodeId {
+    fn from(value: CharacterId) -> Self {
+        Self {
+            tag: Some(CHARACTER_TAG),
+            id: value.into(),
+        }
+    }
+}
+
+impl From<AugmentationId> for NodeId {
+    fn from(value: AugmentationId) -> Self {
+        Self {
+            tag: Some(AUGMENTATION_TAG),
+            id: value.into(),
+        }
+    }
+}
+
+#[Scalar(name = "ID")]
+impl ScalarType for NodeId {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        Id::parse_input(value, None)
+            .map(|id| Self { tag: None, id })
+            .map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        match self.tag {
+            Some(tag) => self.id.to_tagged_value(tag),
+            None => self.id.to_value(),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::str::FromStr;
+
+use async_graphql::{InputValueError, InputValueResult, Scalar, ScalarType, Value};
+
+use super::id::{Id, USER_TAG};
+
+/// Id of a user, sent to the clients as `User:<uuid>`
+///
+/// Ids tagged with another type are rejected when they are parsed, bare UUIDs are accepted as well.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct UserId(pub Id);
+
+impl UserId {
+    pub fn as_id(&self) -> &Id {
+        &self.0
+    }
+}
+
+impl From<UserId> for Id {
+    fn from(value: UserId) -> Self {
+        value.0
+    }
+}
+
+/// Parses an id given on the command line, validated the same way as the ids of the clients
+impl FromStr for UserId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Id::parse_input(Value::String(s.into()), Some(USER_TAG)).map(Self)
+    }
+}
+
+#[Scalar(name = "ID")]
+impl ScalarType for UserId {
+    fn parse(value: Value) -> InputValueResult<Self> {
+        Id::parse_input(value, Some(USER_TAG))
+            .map(Self)
+            .map_err(InputValueError::custom)
+    }
+
+    fn to_value(&self) -> Value {
+        self.0.to_tagged_value(USER_TAG)
+    }
+}
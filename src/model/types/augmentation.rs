@@ -1,16 +1,33 @@
 use async_graphql::Object;
 
-use crate::error::UnimplementedError;
+use crate::model::{
+    enums::augmentation_type::AugmentationType,
+    scalars::{augmentation_id::AugmentationId, id::Id},
+};
 
-pub struct Augmentation;
+#[derive(Clone)]
+pub struct Augmentation {
+    pub id: Id,
+    pub name: String,
+    pub description: String,
+    pub r#type: AugmentationType,
+}
 
 #[Object]
 impl Augmentation {
-    pub async fn name(&self) -> Result<String, UnimplementedError> {
-        Err(UnimplementedError("Augmentation::name".into()))
+    pub async fn id(&self) -> AugmentationId {
+        AugmentationId(self.id.clone())
+    }
+
+    pub async fn name(&self) -> &String {
+        &self.name
+    }
+
+    pub async fn description(&self) -> &String {
+        &self.description
     }
 
-    pub async fn description(&self) -> Result<String, UnimplementedError> {
-        Err(UnimplementedError("Augmentation::description".into()))
+    pub async fn r#type(&self) -> AugmentationType {
+        self.r#type
     }
 }
@@ -0,0 +1,14 @@
+use async_graphql::SimpleObject;
+
+use super::augmentation::Augmentation;
+
+/// Augmentation found by a search
+#[derive(SimpleObject)]
+pub struct AugmentationSearchHit {
+    pub augmentation: Augmentation,
+    /// Part of the name or the description that matched as HTML, the text is escaped and the
+    /// matched terms are wrapped in `<mark>` tags
+    pub snippet: String,
+    /// Relevance of the hit, the higher the better
+    pub score: f64,
+}
@@ -0,0 +1,15 @@
+use async_graphql::{
+    connection::{Connection, OpaqueCursor},
+    SimpleObject,
+};
+
+use crate::{model::interfaces::character::Character, sql_queries::sql_character::CharacterCursor};
+
+pub type CharacterConnection =
+    Connection<OpaqueCursor<CharacterCursor>, Character, CharacterConnectionFields>;
+
+#[derive(SimpleObject)]
+pub struct CharacterConnectionFields {
+    /// Number of all characters in the list, regardless of the page
+    pub total_count: i64,
+}
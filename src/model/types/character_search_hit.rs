@@ -0,0 +1,14 @@
+use async_graphql::SimpleObject;
+
+use crate::model::interfaces::character::Character;
+
+/// Character found by a search
+#[derive(SimpleObject)]
+pub struct CharacterSearchHit {
+    pub character: Character,
+    /// Part of the nickname or the name that matched as HTML, the text is escaped and the
+    /// matched terms are wrapped in `<mark>` tags
+    pub snippet: String,
+    /// Relevance of the hit, the higher the better
+    pub score: f64,
+}
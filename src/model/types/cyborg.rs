@@ -1,16 +1,20 @@
-use async_graphql::Object;
+use async_graphql::{Context, ErrorExtensions, Object};
 
 use crate::{
-    error::UnimplementedError,
     model::{
         enums::{augmentation_type::AugmentationType, character_race::CharacterRace},
-        scalars::id::Id,
+        loaders::ContextDataLoadersExt,
+        scalars::{character_id::CharacterId, id::Id},
     },
+    state::State,
 };
 
 use super::augmentation::Augmentation;
 
+#[derive(Clone)]
 pub struct Cyborg {
+    pub state: State,
+
     pub id: Id,
     pub name: Option<String>,
     pub nickname: String,
@@ -19,8 +23,8 @@ pub struct Cyborg {
 
 #[Object]
 impl Cyborg {
-    pub async fn id(&self) -> &Id {
-        &self.id
+    pub async fn id(&self) -> CharacterId {
+        CharacterId(self.id.clone())
     }
 
     pub async fn name(&self) -> &Option<String> {
@@ -35,17 +39,31 @@ impl Cyborg {
         self.race
     }
 
+    /// Augmentations of the character, only the ones of the given type when `type` is given, the
+    /// argument is optional so the queries written against the earlier schema stay valid
     pub async fn augmentations(
         &self,
-        r#_type: AugmentationType,
-    ) -> Result<Vec<Augmentation>, UnimplementedError> {
-        Err(UnimplementedError("Cyborg::augmentations".into()))
+        ctx: &Context<'_>,
+        r#type: Option<AugmentationType>,
+    ) -> async_graphql::Result<Vec<Augmentation>> {
+        let augmentations = ctx
+            .augmentations_by_character_loader()
+            .load_one(self.id.clone())
+            .await
+            .map_err(|e| e.extend())?
+            .unwrap_or_default();
+
+        Ok(augmentations
+            .into_iter()
+            .filter(|augmentation| r#type.is_none_or(|r#type| augmentation.r#type == r#type))
+            .collect())
     }
 
     pub async fn augmentations_by_type(
         &self,
-        r#_type: AugmentationType,
-    ) -> Result<Vec<Augmentation>, UnimplementedError> {
-        Err(UnimplementedError("Cyborg::augmentations_by_type".into()))
+        ctx: &Context<'_>,
+        r#type: AugmentationType,
+    ) -> async_graphql::Result<Vec<Augmentation>> {
+        self.augmentations(ctx, Some(r#type)).await
     }
 }
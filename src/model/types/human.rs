@@ -1,7 +1,11 @@
 use async_graphql::Object;
 
-use crate::model::{enums::character_race::CharacterRace, scalars::id::Id};
+use crate::model::{
+    enums::character_race::CharacterRace,
+    scalars::{character_id::CharacterId, id::Id},
+};
 
+#[derive(Clone)]
 pub struct Human {
     pub id: Id,
     pub name: Option<String>,
@@ -11,8 +15,8 @@ pub struct Human {
 
 #[Object]
 impl Human {
-    pub async fn id(&self) -> &Id {
-        &self.id
+    pub async fn id(&self) -> CharacterId {
+        CharacterId(self.id.clone())
     }
 
     pub async fn name(&self) -> &Option<String> {
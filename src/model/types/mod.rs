@@ -1,5 +1,8 @@
 pub mod android;
 pub mod augmentation;
+pub mod augmentation_search_hit;
+pub mod character_connection;
+pub mod character_search_hit;
 pub mod cyborg;
 pub mod human;
 pub mod user;
@@ -1,16 +1,27 @@
-use async_graphql::{Description, Object};
+use async_graphql::{
+    connection::{query, Edge, OpaqueCursor},
+    Context, Description, ErrorExtensions, Object,
+};
 
 use crate::{
-    model::{interfaces::character::Character, scalars::id::Id},
-    sql_queries::sql_character::{
-        error::{GetCharacterByIdError, GetCharactersError},
-        get_character_by_id, get_characters,
+    model::{
+        enums::role::Role,
+        guards::role_guard::OwnerOrRoleGuard,
+        inputs::{character_filter::CharacterFilter, character_order::CharacterOrder},
+        interfaces::character::Character,
+        loaders::ContextDataLoadersExt,
+        scalars::{character_id::CharacterId, id::Id, user_id::UserId},
+        types::character_connection::{CharacterConnection, CharacterConnectionFields},
+    },
+    sql_queries::{
+        sql_character::{get_characters_page, CharacterCursor, CharacterPageRange},
+        sql_user_role::get_user_roles,
     },
     state::State,
 };
 
 /// User of the application
-#[derive(Description)]
+#[derive(Clone, Description)]
 pub struct User {
     pub state: State,
 
@@ -32,26 +43,95 @@ impl User {
         &self.nickname
     }
 
-    /// Email address of the user
+    /// Email address of the user, visible to the user and admins
+    #[graphql(guard = "OwnerOrRoleGuard::new(self.id.clone(), Role::Admin)")]
     pub async fn email_address(&self) -> &Option<String> {
         &self.email_address
     }
 
     /// Id of the user
-    pub async fn id(&self) -> &Id {
-        &self.id
+    pub async fn id(&self) -> UserId {
+        UserId(self.id.clone())
+    }
+
+    /// Roles of the user, visible to the user and admins
+    #[graphql(guard = "OwnerOrRoleGuard::new(self.id.clone(), Role::Admin)")]
+    pub async fn roles(&self) -> async_graphql::Result<Vec<Role>> {
+        get_user_roles(&self.state, &self.id)
+            .await
+            .map_err(|e| e.extend())
     }
 
     /// Character of the user with the given id
+    #[graphql(guard = "OwnerOrRoleGuard::new(self.id.clone(), Role::Admin)")]
     pub async fn character_by_id(
         &self,
-        id: Id,
-    ) -> Result<Option<Character>, GetCharacterByIdError> {
-        get_character_by_id(&self.state, &self.id, &id).await
+        ctx: &Context<'_>,
+        id: CharacterId,
+    ) -> async_graphql::Result<Option<Character>> {
+        Ok(ctx
+            .character_loader()
+            .load_one(id.into())
+            .await
+            .map_err(|e| e.extend())?
+            .filter(|owned_character| owned_character.user_id == self.id)
+            .map(|owned_character| owned_character.character))
     }
 
-    /// Characters belonging to the user
-    pub async fn characters(&self) -> Result<Vec<Character>, GetCharactersError> {
-        get_characters(&self.state, &self.id).await
+    /// Characters belonging to the user that match the filter
+    ///
+    /// The characters are sorted by the keys of `orderBy` in turn, then by their ids.
+    #[graphql(guard = "OwnerOrRoleGuard::new(self.id.clone(), Role::Admin)")]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn characters(
+        &self,
+        filter: Option<CharacterFilter>,
+        order_by: Option<Vec<CharacterOrder>>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> async_graphql::Result<CharacterConnection> {
+        query(
+            after,
+            before,
+            first,
+            last,
+            |after: Option<OpaqueCursor<CharacterCursor>>,
+             before: Option<OpaqueCursor<CharacterCursor>>,
+             first,
+             last| async move {
+                let page = get_characters_page(
+                    &self.state,
+                    &self.id,
+                    &filter.unwrap_or_default(),
+                    order_by.as_deref().unwrap_or_default(),
+                    CharacterPageRange {
+                        after: after.as_deref(),
+                        before: before.as_deref(),
+                        first,
+                        last,
+                    },
+                )
+                .await
+                .map_err(|e| e.extend())?;
+
+                let mut connection = CharacterConnection::with_additional_fields(
+                    page.has_previous_page,
+                    page.has_next_page,
+                    CharacterConnectionFields {
+                        total_count: page.total_count,
+                    },
+                );
+                connection.edges.extend(
+                    page.characters
+                        .into_iter()
+                        .map(|(cursor, character)| Edge::new(OpaqueCursor(cursor), character)),
+                );
+
+                Ok::<_, async_graphql::Error>(connection)
+            },
+        )
+        .await
     }
 }
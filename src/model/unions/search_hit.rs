@@ -0,0 +1,11 @@
+use async_graphql::Union;
+
+use crate::model::types::{
+    augmentation_search_hit::AugmentationSearchHit, character_search_hit::CharacterSearchHit,
+};
+
+#[derive(Union)]
+pub enum SearchHit {
+    Character(CharacterSearchHit),
+    Augmentation(AugmentationSearchHit),
+}
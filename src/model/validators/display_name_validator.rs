@@ -0,0 +1,118 @@
+use async_graphql::{CustomValidator, InputValueError};
+
+const MIN_DISPLAY_NAME_LENGTH: usize = 3;
+const MAX_DISPLAY_NAME_LENGTH: usize = 32;
+const RESERVED_DISPLAY_NAMES: &[&str] = &[
+    "admin",
+    "administrator",
+    "moderator",
+    "root",
+    "support",
+    "system",
+];
+
+/// Validates display names before they reach the database
+pub struct DisplayNameValidator;
+
+impl CustomValidator<String> for DisplayNameValidator {
+    fn check(&self, value: &String) -> Result<(), InputValueError<String>> {
+        let length = value.chars().count();
+        if !(MIN_DISPLAY_NAME_LENGTH..=MAX_DISPLAY_NAME_LENGTH).contains(&length) {
+            return Err(InputValueError::custom(format!(
+                "display name must be between {MIN_DISPLAY_NAME_LENGTH} and {MAX_DISPLAY_NAME_LENGTH} characters long, actual length = {length}"
+            )));
+        }
+
+        if let Some(invalid_char) = value
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-' || *c == '.'))
+        {
+            return Err(InputValueError::custom(format!(
+                "display name can only contain ASCII letters, digits, '_', '-' and '.', invalid character = '{invalid_char}'"
+            )));
+        }
+
+        if RESERVED_DISPLAY_NAMES
+            .iter()
+            .any(|reserved_name| reserved_name.eq_ignore_ascii_case(value))
+        {
+            return Err(InputValueError::custom(format!(
+                "display name is reserved, display_name = '{value}'"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::Pos;
+
+    use super::*;
+
+    fn check(display_name: &str) -> Result<(), String> {
+        DisplayNameValidator
+            .check(&display_name.to_owned())
+            .map_err(|e| e.into_server_error(Pos::default()).message)
+    }
+
+    #[test]
+    fn valid_display_names_are_accepted() {
+        for display_name in ["abc", "Adam_Jensen", "jc.denton-2052", &"a".repeat(32)] {
+            assert_eq!(
+                check(display_name),
+                Ok(()),
+                "display_name = '{display_name}'"
+            );
+        }
+    }
+
+    #[test]
+    fn display_names_of_invalid_length_are_rejected() {
+        for display_name in ["", "ab", &"a".repeat(33)] {
+            assert!(
+                check(display_name).is_err_and(|message| message.contains("characters long")),
+                "display_name = '{display_name}'"
+            );
+        }
+    }
+
+    #[test]
+    fn length_is_counted_in_characters() {
+        // 3 characters, 6 bytes, rejected by the charset and not by the length
+        assert!(check("äöü").is_err_and(|message| message.contains("invalid character = 'ä'")));
+    }
+
+    #[test]
+    fn display_names_with_invalid_characters_are_rejected() {
+        for display_name in [
+            "adam jensen",
+            "adam@jensen",
+            "<script>",
+            "adam\njensen",
+            "jénsen",
+        ] {
+            assert!(
+                check(display_name).is_err_and(|message| message.contains("invalid character")),
+                "display_name = '{display_name}'"
+            );
+        }
+    }
+
+    #[test]
+    fn reserved_display_names_are_rejected_in_any_case() {
+        for display_name in ["admin", "Admin", "ROOT", "Moderator", "system"] {
+            assert!(
+                check(display_name).is_err_and(|message| message.contains("reserved")),
+                "display_name = '{display_name}'"
+            );
+        }
+    }
+
+    #[test]
+    fn names_containing_a_reserved_name_are_accepted() {
+        assert_eq!(check("admin2"), Ok(()));
+        assert_eq!(check("the_root"), Ok(()));
+    }
+}
@@ -0,0 +1 @@
+pub mod display_name_validator;
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+use crate::log_location;
+
+struct ShutdownInner {
+    triggered: watch::Sender<bool>,
+    active_task_count: watch::Sender<usize>,
+}
+
+/// Tells the long-lived work of the server (e.g., WebSocket connections) that the server is
+/// shutting down, and lets the server wait for that work to finish
+#[derive(Clone)]
+pub struct Shutdown(Arc<ShutdownInner>);
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self(Arc::new(ShutdownInner {
+            triggered: watch::Sender::new(false),
+            active_task_count: watch::Sender::new(0),
+        }))
+    }
+
+    pub fn trigger(&self) {
+        self.0.triggered.send_replace(true);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        *self.0.triggered.borrow()
+    }
+
+    /// Resolves when the shutdown is triggered, immediately if it has already been
+    pub async fn triggered(&self) {
+        let _ = self
+            .0
+            .triggered
+            .subscribe()
+            .wait_for(|triggered| *triggered)
+            .await;
+    }
+
+    /// Registers a task the shutdown waits for, until the returned guard is dropped
+    pub fn track_task(&self) -> TaskGuard {
+        self.0.active_task_count.send_modify(|count| *count += 1);
+
+        TaskGuard {
+            shutdown: self.clone(),
+        }
+    }
+
+    /// Resolves when every tracked task has finished
+    pub async fn tasks_finished(&self) {
+        let _ = self
+            .0
+            .active_task_count
+            .subscribe()
+            .wait_for(|count| *count == 0)
+            .await;
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct TaskGuard {
+    shutdown: Shutdown,
+}
+
+impl Drop for TaskGuard {
+    fn drop(&mut self) {
+        self.shutdown
+            .0
+            .active_task_count
+            .send_modify(|count| *count -= 1);
+    }
+}
+
+/// Resolves when the process is asked to stop with Ctrl+C or, on Unix, with `SIGTERM`
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("{}, cannot listen for Ctrl+C, error = {e}", log_location!());
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                log::error!(
+                    "{}, cannot listen for SIGTERM, error = {e}",
+                    log_location!()
+                );
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => log::info!("received Ctrl+C"),
+        _ = terminate => log::info!("received SIGTERM"),
+    }
+}
@@ -1,5 +1,23 @@
 pub mod sql_android;
+pub mod sql_augmentation;
 pub mod sql_character;
 pub mod sql_cyborg;
 pub mod sql_human;
+pub mod sql_id;
+pub mod sql_migration;
+pub mod sql_search;
 pub mod sql_user;
+pub mod sql_user_role;
+
+use crate::model::scalars::id::Id;
+
+/// Encodes the ids as a JSON array, the array is expanded with `json_each` in `IN (...)` filters,
+/// so the number of ids does not change the query
+fn ids_to_json_array(ids: &[Id]) -> String {
+    serde_json::Value::from(
+        ids.iter()
+            .map(|id| id.as_string_ref().as_str())
+            .collect::<Vec<_>>(),
+    )
+    .to_string()
+}
@@ -1,5 +1,7 @@
 use error::{CannotConvertSqlAndroidToAndroid, GetAndroidByIdError};
 
+use sqlx::SqliteExecutor;
+
 use crate::{
     error::SqlxErrorConverter,
     log_location,
@@ -22,6 +24,21 @@ pub async fn get_android_by_id(
     state: &State,
     user_id: &Id,
     character_id: &Id,
+) -> Result<Option<Android>, GetAndroidByIdError> {
+    fetch_android_by_id(
+        state.database.connection_pool_ref(),
+        state,
+        user_id,
+        character_id,
+    )
+    .await
+}
+
+pub(super) async fn fetch_android_by_id(
+    executor: impl SqliteExecutor<'_>,
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
 ) -> Result<Option<Android>, GetAndroidByIdError> {
     let user_id_str = user_id.as_string_ref();
     let character_id_str = character_id.as_string_ref();
@@ -43,7 +60,7 @@ pub async fn get_android_by_id(
         user_id_str,
         character_id_str,
     )
-    .fetch_optional(state.database.connection_pool_ref())
+    .fetch_optional(executor)
     .await
     .to_sqlx_error_result()
     .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
@@ -57,7 +74,7 @@ pub async fn get_android_by_id(
 impl SqlAndroid {
     pub fn try_into_android(
         self,
-        _state: &State,
+        state: &State,
     ) -> Result<Android, CannotConvertSqlAndroidToAndroid> {
         const EXPECTED_CHARACTER_RACE: CharacterRace = CharacterRace::Android;
 
@@ -65,6 +82,7 @@ impl SqlAndroid {
 
         match character_race {
             EXPECTED_CHARACTER_RACE => Ok(Android {
+                state: state.clone(),
                 id: self.Id,
                 name: self.Name,
                 nickname: self.Nickname,
@@ -80,8 +98,11 @@ impl SqlAndroid {
 }
 
 pub mod error {
+    use async_graphql::Value;
+
     use crate::{
-        error::SqlxError, model::enums::character_race::error::InvalidCharacterRace,
+        error::{ErrorCode, SqlxError},
+        model::enums::character_race::error::InvalidCharacterRace,
         sql_queries::sql_character::error::CharacterRaceMismatchError,
     };
 
@@ -125,4 +146,38 @@ pub mod error {
             CannotConvertSqlAndroidToAndroid,
         ),
     }
+
+    impl ErrorCode for CannotConvertSqlAndroidToAndroid {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CharacterRaceMismatchError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CharacterRaceMismatchError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetAndroidByIdError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotConvertSqlAndroidToAndroid(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotConvertSqlAndroidToAndroid(e) => e.fields(),
+            }
+        }
+    }
 }
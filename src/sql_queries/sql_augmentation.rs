@@ -0,0 +1,844 @@
+use error::{
+    CannotConvertSqlAugmentationToAugmentation, CannotFindAugmentationById,
+    CharacterNotAugmentable, GetAugmentationsError, InstallAugmentationError,
+    RemoveAugmentationError, ReplaceAugmentationError, UnsupportedAugmentationType,
+};
+use std::collections::HashMap;
+
+use sqlx::{SqliteConnection, SqliteExecutor};
+
+use crate::{
+    error::{SqlxError, SqlxErrorConverter},
+    log_location,
+    model::{
+        enums::{augmentation_type::AugmentationType, character_race::CharacterRace},
+        inputs::augmentation_input::AugmentationInput,
+        scalars::id::Id,
+        types::augmentation::Augmentation,
+    },
+    state::State,
+};
+
+use super::{ids_to_json_array, sql_character::error::CannotFindCharacterById};
+
+#[derive(sqlx::Type)]
+#[allow(non_snake_case)]
+pub(super) struct SqlAugmentation {
+    pub Id: Id,
+    pub Name: String,
+    pub Description: String,
+    pub Type: String,
+}
+
+pub async fn get_augmentations(
+    state: &State,
+    character_id: &Id,
+    augmentation_type: Option<AugmentationType>,
+) -> Result<Vec<Augmentation>, GetAugmentationsError> {
+    fetch_augmentations(
+        state.database.connection_pool_ref(),
+        state,
+        character_id,
+        augmentation_type,
+    )
+    .await
+}
+
+pub(super) async fn fetch_augmentations(
+    executor: impl SqliteExecutor<'_>,
+    state: &State,
+    character_id: &Id,
+    augmentation_type: Option<AugmentationType>,
+) -> Result<Vec<Augmentation>, GetAugmentationsError> {
+    let character_id_str = character_id.as_string_ref();
+    let augmentation_type_str = augmentation_type.map(<&str>::from);
+
+    let records = sqlx::query_as!(
+        SqlAugmentation,
+        "
+            SELECT
+                Id, Name, Description, Type
+            FROM
+                Augmentations
+            WHERE
+                CharacterId = ?1 AND (?2 IS NULL OR Type = ?2)
+            ORDER BY
+                Id
+        ",
+        character_id_str,
+        augmentation_type_str,
+    )
+    .fetch_all(executor)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| record.try_into_augmentation(state))
+        .collect::<Result<_, _>>()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?)
+}
+
+/// Loads the augmentations of the given characters, every character gets an entry even if it has
+/// no augmentations
+pub async fn get_augmentations_by_character_ids(
+    state: &State,
+    character_ids: &[Id],
+) -> Result<HashMap<Id, Vec<Augmentation>>, GetAugmentationsError> {
+    let character_ids_json = ids_to_json_array(character_ids);
+
+    let records = sqlx::query!(
+        "
+            SELECT
+                CharacterId, Id, Name, Description, Type
+            FROM
+                Augmentations
+            WHERE
+                CharacterId IN (SELECT value FROM json_each(?))
+            ORDER BY
+                Id
+        ",
+        character_ids_json,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let mut ret: HashMap<Id, Vec<Augmentation>> = character_ids
+        .iter()
+        .map(|character_id| (character_id.clone(), Vec::new()))
+        .collect();
+
+    for record in records {
+        let augmentation = SqlAugmentation {
+            Id: record.Id.into(),
+            Name: record.Name,
+            Description: record.Description,
+            Type: record.Type,
+        }
+        .try_into_augmentation(state)
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+        ret.entry(record.CharacterId.into())
+            .or_default()
+            .push(augmentation);
+    }
+
+    Ok(ret)
+}
+
+/// Augmentation together with the id of the user whose character holds it
+#[derive(Clone)]
+pub struct OwnedAugmentation {
+    pub user_id: Id,
+    pub augmentation: Augmentation,
+}
+
+/// Loads the augmentations with the given ids regardless of the user they belong to, ids without
+/// an augmentation are left out of the result
+pub async fn get_augmentations_by_ids(
+    state: &State,
+    augmentation_ids: &[Id],
+) -> Result<HashMap<Id, OwnedAugmentation>, GetAugmentationsError> {
+    let augmentation_ids_json = ids_to_json_array(augmentation_ids);
+
+    let records = sqlx::query!(
+        "
+            SELECT
+                Augmentations.Id as Id,
+                Augmentations.Name as Name,
+                Augmentations.Description as Description,
+                Augmentations.Type as Type,
+                Characters.UserId as UserId
+            FROM
+                Augmentations
+                    JOIN Characters ON Augmentations.CharacterId = Characters.Id
+            WHERE
+                Augmentations.Id IN (SELECT value FROM json_each(?))
+        ",
+        augmentation_ids_json,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let mut ret = HashMap::new();
+
+    for record in records {
+        let augmentation_id: Id = record.Id.into();
+        let augmentation = SqlAugmentation {
+            Id: augmentation_id.clone(),
+            Name: record.Name,
+            Description: record.Description,
+            Type: record.Type,
+        }
+        .try_into_augmentation(state)
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+        ret.insert(
+            augmentation_id,
+            OwnedAugmentation {
+                user_id: record.UserId.into(),
+                augmentation,
+            },
+        );
+    }
+
+    Ok(ret)
+}
+
+/// Installs a new augmentation into a character of the user
+///
+/// Only androids and cyborgs can hold augmentations, and the type of the augmentation has to be
+/// supported by the race of the character.
+pub async fn install_augmentation(
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
+    augmentation_definition: &AugmentationInput,
+) -> Result<Augmentation, InstallAugmentationError> {
+    let user_id_str = user_id.as_string_ref();
+    let character_id_str = character_id.as_string_ref();
+
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let race: CharacterRace = sqlx::query!(
+        "
+            SELECT
+                Race
+            FROM
+                Characters
+            WHERE
+                Id = ? AND UserId = ?
+        ",
+        character_id_str,
+        user_id_str,
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindCharacterById(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .Race
+    .parse()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    if !race.is_augmentable() {
+        return Err(CharacterNotAugmentable {
+            character_id: character_id.clone(),
+            race,
+        }
+        .into());
+    }
+
+    check_augmentation_type(race, augmentation_definition.r#type)?;
+
+    let augmentation =
+        insert_augmentation(&mut transaction, character_id, augmentation_definition).await?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(augmentation)
+}
+
+/// Removes an augmentation from a character of the user, returns the removed augmentation
+pub async fn remove_augmentation(
+    state: &State,
+    user_id: &Id,
+    augmentation_id: &Id,
+) -> Result<Augmentation, RemoveAugmentationError> {
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let (augmentation, _) = fetch_owned_augmentation(&mut transaction, user_id, augmentation_id)
+        .await?
+        .ok_or_else(|| CannotFindAugmentationById(augmentation_id.clone()))
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    delete_augmentation(&mut transaction, augmentation_id).await?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(augmentation.try_into_augmentation(state)?)
+}
+
+/// Replaces an augmentation of a character of the user with a new one, the new augmentation gets
+/// a new id
+pub async fn replace_augmentation(
+    state: &State,
+    user_id: &Id,
+    augmentation_id: &Id,
+    augmentation_definition: &AugmentationInput,
+) -> Result<Augmentation, ReplaceAugmentationError> {
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let (_, owner) = fetch_owned_augmentation(&mut transaction, user_id, augmentation_id)
+        .await?
+        .ok_or_else(|| CannotFindAugmentationById(augmentation_id.clone()))
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let race: CharacterRace = owner
+        .Race
+        .parse()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    check_augmentation_type(race, augmentation_definition.r#type)?;
+
+    delete_augmentation(&mut transaction, augmentation_id).await?;
+    let augmentation = insert_augmentation(
+        &mut transaction,
+        &owner.CharacterId,
+        augmentation_definition,
+    )
+    .await?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(augmentation)
+}
+
+#[allow(non_snake_case)]
+struct SqlAugmentationOwner {
+    CharacterId: Id,
+    Race: String,
+}
+
+fn check_augmentation_type(
+    race: CharacterRace,
+    augmentation_type: AugmentationType,
+) -> Result<(), UnsupportedAugmentationType> {
+    if race.supports_augmentation_type(augmentation_type) {
+        Ok(())
+    } else {
+        Err(UnsupportedAugmentationType {
+            race,
+            augmentation_type,
+        })
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))
+    }
+}
+
+async fn fetch_owned_augmentation(
+    connection: &mut SqliteConnection,
+    user_id: &Id,
+    augmentation_id: &Id,
+) -> Result<Option<(SqlAugmentation, SqlAugmentationOwner)>, SqlxError> {
+    let user_id_str = user_id.as_string_ref();
+    let augmentation_id_str = augmentation_id.as_string_ref();
+
+    let record = sqlx::query!(
+        "
+            SELECT
+                Augmentations.Id, Augmentations.Name, Augmentations.Description,
+                Augmentations.Type, Augmentations.CharacterId, Characters.Race
+            FROM
+                Augmentations
+                    JOIN Characters ON Augmentations.CharacterId = Characters.Id
+            WHERE
+                Augmentations.Id = ? AND Characters.UserId = ?
+        ",
+        augmentation_id_str,
+        user_id_str,
+    )
+    .fetch_optional(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(record.map(|record| {
+        (
+            SqlAugmentation {
+                Id: record.Id.into(),
+                Name: record.Name,
+                Description: record.Description,
+                Type: record.Type,
+            },
+            SqlAugmentationOwner {
+                CharacterId: record.CharacterId.into(),
+                Race: record.Race,
+            },
+        )
+    }))
+}
+
+async fn insert_augmentation(
+    connection: &mut SqliteConnection,
+    character_id: &Id,
+    augmentation_definition: &AugmentationInput,
+) -> Result<Augmentation, SqlxError> {
+    let augmentation_id: Id = uuid::Uuid::new_v4().to_string().into();
+    let augmentation_id_str = augmentation_id.as_string_ref();
+    let character_id_str = character_id.as_string_ref();
+    let type_str: &str = augmentation_definition.r#type.into();
+
+    sqlx::query!(
+        "
+            INSERT INTO
+                Ids (Id, Type)
+            VALUES
+                (?, 'Augmentation')
+        ",
+        augmentation_id_str,
+    )
+    .execute(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    sqlx::query!(
+        "
+            INSERT INTO
+                Augmentations (Id, CharacterId, Name, Description, Type)
+            VALUES
+                (?, ?, ?, ?, ?)
+        ",
+        augmentation_id_str,
+        character_id_str,
+        augmentation_definition.name,
+        augmentation_definition.description,
+        type_str,
+    )
+    .execute(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(Augmentation {
+        id: augmentation_id,
+        name: augmentation_definition.name.clone(),
+        description: augmentation_definition.description.clone(),
+        r#type: augmentation_definition.r#type,
+    })
+}
+
+/// Deletes every augmentation of the character, used when the character itself is deleted
+pub(super) async fn delete_augmentations_of_character(
+    connection: &mut SqliteConnection,
+    character_id: &Id,
+) -> Result<(), SqlxError> {
+    let character_id_str = character_id.as_string_ref();
+
+    let records = sqlx::query!(
+        "
+            DELETE FROM
+                Augmentations
+            WHERE
+                CharacterId = ?
+            RETURNING
+                Id
+        ",
+        character_id_str,
+    )
+    .fetch_all(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    for record in records {
+        sqlx::query!(
+            "
+                DELETE FROM
+                    Ids
+                WHERE
+                    Id = ?
+            ",
+            record.Id,
+        )
+        .execute(&mut *connection)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+    }
+
+    Ok(())
+}
+
+pub(super) async fn delete_augmentation(
+    connection: &mut SqliteConnection,
+    augmentation_id: &Id,
+) -> Result<(), SqlxError> {
+    let augmentation_id_str = augmentation_id.as_string_ref();
+
+    sqlx::query!(
+        "
+            DELETE FROM
+                Augmentations
+            WHERE
+                Id = ?
+        ",
+        augmentation_id_str,
+    )
+    .execute(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    sqlx::query!(
+        "
+            DELETE FROM
+                Ids
+            WHERE
+                Id = ?
+        ",
+        augmentation_id_str,
+    )
+    .execute(&mut *connection)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(())
+}
+
+impl SqlAugmentation {
+    pub fn try_into_augmentation(
+        self,
+        _state: &State,
+    ) -> Result<Augmentation, CannotConvertSqlAugmentationToAugmentation> {
+        Ok(Augmentation {
+            id: self.Id,
+            name: self.Name,
+            description: self.Description,
+            r#type: self.Type.parse()?,
+        })
+    }
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, InputType, Value};
+
+    use crate::{
+        error::{ErrorCode, SqlxError},
+        model::{
+            enums::{
+                augmentation_type::{error::InvalidAugmentationType, AugmentationType},
+                character_race::{error::InvalidCharacterRace, CharacterRace},
+            },
+            scalars::id::Id,
+        },
+        sql_queries::sql_character::error::CannotFindCharacterById,
+    };
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CannotFindAugmentationById: augmentation_id = '{0:?}'")]
+    pub struct CannotFindAugmentationById(pub Id);
+
+    impl ErrorCode for CannotFindAugmentationById {
+        fn code(&self) -> &'static str {
+            "AUGMENTATION_NOT_FOUND"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![("augmentationId", self.0.to_value())]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CharacterNotAugmentable: character_id = '{character_id:?}', race = '{race}'")]
+    pub struct CharacterNotAugmentable {
+        pub character_id: Id,
+        pub race: CharacterRace,
+    }
+
+    impl ErrorCode for CharacterNotAugmentable {
+        fn code(&self) -> &'static str {
+            "CHARACTER_NOT_AUGMENTABLE"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("characterId", self.character_id.to_value()),
+                ("race", self.race.to_value()),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error(
+        "UnsupportedAugmentationType: race = '{race}', augmentation_type = '{augmentation_type}'"
+    )]
+    pub struct UnsupportedAugmentationType {
+        pub race: CharacterRace,
+        pub augmentation_type: AugmentationType,
+    }
+
+    impl ErrorCode for UnsupportedAugmentationType {
+        fn code(&self) -> &'static str {
+            "UNSUPPORTED_AUGMENTATION_TYPE"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("race", self.race.to_value()),
+                ("augmentationType", self.augmentation_type.to_value()),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum CannotConvertSqlAugmentationToAugmentation {
+        #[error("CannotConvertSqlAugmentationToAugmentation: '{0}'")]
+        InvalidAugmentationType(
+            #[from]
+            #[source]
+            InvalidAugmentationType,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetAugmentationsError {
+        #[error("GetAugmentationsError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetAugmentationsError: '{0}'")]
+        CannotConvertSqlAugmentationToAugmentation(
+            #[from]
+            #[source]
+            CannotConvertSqlAugmentationToAugmentation,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum InstallAugmentationError {
+        #[error("InstallAugmentationError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("InstallAugmentationError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("InstallAugmentationError: '{0}'")]
+        CannotFindCharacterById(
+            #[from]
+            #[source]
+            CannotFindCharacterById,
+        ),
+
+        #[error("InstallAugmentationError: '{0}'")]
+        CharacterNotAugmentable(
+            #[from]
+            #[source]
+            CharacterNotAugmentable,
+        ),
+
+        #[error("InstallAugmentationError: '{0}'")]
+        UnsupportedAugmentationType(
+            #[from]
+            #[source]
+            UnsupportedAugmentationType,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum RemoveAugmentationError {
+        #[error("RemoveAugmentationError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("RemoveAugmentationError: '{0}'")]
+        CannotFindAugmentationById(
+            #[from]
+            #[source]
+            CannotFindAugmentationById,
+        ),
+
+        #[error("RemoveAugmentationError: '{0}'")]
+        CannotConvertSqlAugmentationToAugmentation(
+            #[from]
+            #[source]
+            CannotConvertSqlAugmentationToAugmentation,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum ReplaceAugmentationError {
+        #[error("ReplaceAugmentationError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("ReplaceAugmentationError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("ReplaceAugmentationError: '{0}'")]
+        CannotFindAugmentationById(
+            #[from]
+            #[source]
+            CannotFindAugmentationById,
+        ),
+
+        #[error("ReplaceAugmentationError: '{0}'")]
+        UnsupportedAugmentationType(
+            #[from]
+            #[source]
+            UnsupportedAugmentationType,
+        ),
+    }
+
+    impl ErrorCode for CannotConvertSqlAugmentationToAugmentation {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::InvalidAugmentationType(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::InvalidAugmentationType(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetAugmentationsError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetAugmentationsError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for InstallAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotFindCharacterById(e) => e.code(),
+                Self::CharacterNotAugmentable(e) => e.code(),
+                Self::UnsupportedAugmentationType(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotFindCharacterById(e) => e.fields(),
+                Self::CharacterNotAugmentable(e) => e.fields(),
+                Self::UnsupportedAugmentationType(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for InstallAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for RemoveAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotFindAugmentationById(e) => e.code(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotFindAugmentationById(e) => e.fields(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for RemoveAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for ReplaceAugmentationError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotFindAugmentationById(e) => e.code(),
+                Self::UnsupportedAugmentationType(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotFindAugmentationById(e) => e.fields(),
+                Self::UnsupportedAugmentationType(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for ReplaceAugmentationError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+}
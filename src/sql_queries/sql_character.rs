@@ -1,16 +1,46 @@
-use error::{GetCharacterByIdError, GetCharactersError};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use error::{
+    CannotConvertSqlCharacterToCharacter, CannotFindCharacterById, CannotFindConvertedCharacter,
+    CannotFindCreatedCharacter, CannotFindUpdatedCharacter, CharacterNicknameAlreadyTaken,
+    CharacterPageSizeTooLarge, ConvertCharacterRaceError, CreateCharacterError,
+    DeleteCharacterError, GetCharacterByIdError, GetCharactersError, GetCharactersPageError,
+    IncompatibleAugmentations, InvalidCharacterCursor, UpdateCharacterError,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, FromRow, QueryBuilder, Row, Sqlite, SqliteConnection};
 
 use crate::{
-    error::SqlxErrorConverter,
+    error::{SqlxError, SqlxErrorConverter},
     log_location,
     model::{
-        enums::character_race::CharacterRace, interfaces::character::Character, scalars::id::Id,
+        enums::{
+            character_order_field::CharacterOrderField, character_race::CharacterRace,
+            incompatible_augmentation_policy::IncompatibleAugmentationPolicy,
+            order_direction::OrderDirection,
+        },
+        inputs::{
+            character_creation_input::CharacterCreationInput, character_filter::CharacterFilter,
+            character_order::CharacterOrder, character_patch_input::CharacterPatchInput,
+        },
+        interfaces::character::Character,
+        scalars::id::Id,
     },
     state::State,
 };
 
 use super::{
-    sql_android::get_android_by_id, sql_cyborg::get_cyborg_by_id, sql_human::get_human_by_id,
+    ids_to_json_array,
+    sql_android::{fetch_android_by_id, get_android_by_id, SqlAndroid},
+    sql_augmentation::{
+        delete_augmentation, delete_augmentations_of_character, fetch_augmentations,
+    },
+    sql_cyborg::{fetch_cyborg_by_id, get_cyborg_by_id, SqlCyborg},
+    sql_human::{fetch_human_by_id, get_human_by_id, SqlHuman},
+    sql_user::error::CannotFindUserById,
 };
 
 pub async fn get_character_by_id(
@@ -59,146 +89,1822 @@ pub async fn get_character_by_id(
     })
 }
 
-pub async fn get_characters(
+/// Character together with the id of the user it belongs to
+#[derive(Clone)]
+pub struct OwnedCharacter {
+    pub user_id: Id,
+    pub character: Character,
+}
+
+/// Number of characters on a page when the size of the page is not given
+pub const DEFAULT_CHARACTER_PAGE_SIZE: usize = 20;
+
+/// Largest number of characters that can be requested on a page
+pub const MAX_CHARACTER_PAGE_SIZE: usize = 100;
+
+/// Value of a sort key of a character
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum CharacterSortValue {
+    Integer(i64),
+    Text(String),
+}
+
+/// Position of a character in a sorted character list, the key of the keyset pagination
+///
+/// The cursor holds the order it was created with, so it cannot be used with a different order.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CharacterCursor {
+    order: Vec<(CharacterOrderField, OrderDirection)>,
+    /// Values of the sort keys followed by the id of the character
+    values: Vec<CharacterSortValue>,
+}
+
+impl CharacterCursor {
+    /// Whether the cursor was created with the order and holds a value for each of its sort keys
+    fn is_created_with(&self, order: &[(CharacterOrderField, OrderDirection)]) -> bool {
+        self.order == order && self.values.len() == order.len() + 1
+    }
+}
+
+/// Part of the character list requested by the arguments of a Relay connection
+///
+/// `first` is applied before `last`, and only `last` pages backwards from `before`.
+#[derive(Clone, Copy, Default)]
+pub struct CharacterPageRange<'a> {
+    pub after: Option<&'a CharacterCursor>,
+    pub before: Option<&'a CharacterCursor>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+}
+
+/// Page of the character list of a user
+pub struct CharacterPage {
+    pub characters: Vec<(CharacterCursor, Character)>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+    pub total_count: i64,
+}
+
+/// Row of a character joined with the race tables, only the id column of the race table that
+/// holds the character is set
+#[derive(sqlx::Type)]
+#[allow(non_snake_case)]
+pub(super) struct SqlCharacter {
+    pub Id: Id,
+    pub UserId: Id,
+    pub Name: Option<String>,
+    pub Nickname: String,
+    pub Race: String,
+    pub CreatedAt: i64,
+    pub AndroidId: Option<String>,
+    pub CyborgId: Option<String>,
+    pub HumanId: Option<String>,
+}
+
+/// Key of the order of a character list, the list is always ordered by the ids of the characters
+/// last
+struct CharacterSortKey {
+    field: Option<CharacterOrderField>,
+    direction: OrderDirection,
+}
+
+impl CharacterSortKey {
+    fn expression(&self) -> &'static str {
+        match self.field {
+            Some(CharacterOrderField::Nickname) => "Characters.Nickname",
+            Some(CharacterOrderField::Name) => "IFNULL(Characters.Name, '')",
+            Some(CharacterOrderField::CreatedAt) => "Characters.CreatedAt",
+            Some(CharacterOrderField::Race) => "Characters.Race",
+            None => "Characters.Id",
+        }
+    }
+
+    fn value_of(&self, record: &SqlCharacter) -> CharacterSortValue {
+        match self.field {
+            Some(CharacterOrderField::Nickname) => {
+                CharacterSortValue::Text(record.Nickname.clone())
+            }
+            Some(CharacterOrderField::Name) => {
+                CharacterSortValue::Text(record.Name.clone().unwrap_or_default())
+            }
+            Some(CharacterOrderField::CreatedAt) => CharacterSortValue::Integer(record.CreatedAt),
+            Some(CharacterOrderField::Race) => CharacterSortValue::Text(record.Race.clone()),
+            None => CharacterSortValue::Text(record.Id.as_string_ref().clone()),
+        }
+    }
+
+    /// Returns whether the key is sorted descending in the direction of the paging
+    fn is_descending(&self, backwards: bool) -> bool {
+        (self.direction == OrderDirection::Desc) != backwards
+    }
+}
+
+fn character_sort_keys(order: &[CharacterOrder]) -> Vec<CharacterSortKey> {
+    order
+        .iter()
+        .map(|order| CharacterSortKey {
+            field: Some(order.field),
+            direction: order.direction,
+        })
+        .chain([CharacterSortKey {
+            field: None,
+            direction: OrderDirection::Asc,
+        }])
+        .collect()
+}
+
+fn push_sort_value(builder: &mut QueryBuilder<'_, Sqlite>, value: &CharacterSortValue) {
+    match value {
+        CharacterSortValue::Integer(value) => builder.push_bind(*value),
+        CharacterSortValue::Text(value) => builder.push_bind(value.clone()),
+    };
+}
+
+/// Escapes the wildcards of a `LIKE` pattern, the pattern has to be used with `ESCAPE '\'`
+fn escape_like_pattern(pattern: &str) -> String {
+    pattern
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Pushes the conditions selecting the characters of the user that match the filter
+fn push_character_filter(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    user_id: &Id,
+    filter: &CharacterFilter,
+) {
+    builder
+        .push(" Characters.UserId = ")
+        .push_bind(user_id.as_string_ref().clone());
+
+    if let Some(races) = &filter.race_in {
+        builder.push(" AND Characters.Race IN (");
+
+        let mut separated = builder.separated(", ");
+        for race in races {
+            separated.push_bind(<&str>::from(*race));
+        }
+        separated.push_unseparated(")");
+    }
+
+    if let Some(prefix) = &filter.nickname_starts_with {
+        builder
+            .push(" AND Characters.Nickname LIKE ")
+            .push_bind(format!("{}%", escape_like_pattern(prefix)))
+            .push(" ESCAPE '\\'");
+    }
+
+    if let Some(part) = &filter.nickname_contains {
+        builder
+            .push(" AND Characters.Nickname LIKE ")
+            .push_bind(format!("%{}%", escape_like_pattern(part)))
+            .push(" ESCAPE '\\'");
+    }
+
+    match filter.has_name {
+        Some(true) => {
+            builder.push(" AND Characters.Name IS NOT NULL");
+        }
+        Some(false) => {
+            builder.push(" AND Characters.Name IS NULL");
+        }
+        None => (),
+    }
+
+    if let Some(augmentation_type) = filter.has_augmentation_type {
+        builder
+            .push(
+                " AND EXISTS (SELECT 1 FROM Augmentations \
+                WHERE Augmentations.CharacterId = Characters.Id AND Augmentations.Type = ",
+            )
+            .push_bind(<&str>::from(augmentation_type))
+            .push(")");
+    }
+}
+
+/// Pushes the condition selecting the characters that come after the cursor in the direction of
+/// the paging
+///
+/// With the keys `k1, k2` and the cursor values `v1, v2` the condition is
+/// `(k1 > v1 OR (k1 = v1 AND (k2 > v2)))`, where `<` is used for the descending keys.
+fn push_keyset_condition(
+    builder: &mut QueryBuilder<'_, Sqlite>,
+    sort_keys: &[CharacterSortKey],
+    cursor: &CharacterCursor,
+    backwards: bool,
+) {
+    let mut open_parentheses = 0;
+
+    for (index, (sort_key, value)) in sort_keys.iter().zip(&cursor.values).enumerate() {
+        let operator = if sort_key.is_descending(backwards) {
+            " < "
+        } else {
+            " > "
+        };
+
+        builder.push("(").push(sort_key.expression()).push(operator);
+        push_sort_value(builder, value);
+        open_parentheses += 1;
+
+        if index + 1 < sort_keys.len() {
+            builder
+                .push(" OR (")
+                .push(sort_key.expression())
+                .push(" = ");
+            push_sort_value(builder, value);
+            builder.push(" AND ");
+            open_parentheses += 1;
+        }
+    }
+
+    builder.push(")".repeat(open_parentheses));
+}
+
+/// Loads a page of the characters of the user that match the filter, sorted by the given order
+///
+/// Rows are selected with keyset pagination, so the cost of a page does not depend on its
+/// position. The filter and the order are translated into bound parameters, user input is never
+/// part of the SQL text.
+pub async fn get_characters_page(
     state: &State,
     user_id: &Id,
-) -> Result<Vec<Character>, GetCharactersError> {
-    let user_id_str = user_id.as_string_ref();
+    filter: &CharacterFilter,
+    order: &[CharacterOrder],
+    range: CharacterPageRange<'_>,
+) -> Result<CharacterPage, GetCharactersPageError> {
+    for page_size in [range.first, range.last].into_iter().flatten() {
+        if page_size > MAX_CHARACTER_PAGE_SIZE {
+            return Err(CharacterPageSizeTooLarge {
+                requested: page_size,
+                max: MAX_CHARACTER_PAGE_SIZE,
+            }
+            .into());
+        }
+    }
+
+    let sort_keys = character_sort_keys(order);
+    let cursor_order: Vec<_> = order
+        .iter()
+        .map(|order| (order.field, order.direction))
+        .collect();
+
+    for cursor in [range.after, range.before].into_iter().flatten() {
+        if !cursor.is_created_with(&cursor_order) {
+            return Err(InvalidCharacterCursor.into());
+        }
+    }
+
+    let backwards = range.first.is_none() && range.last.is_some();
+    let limit = range
+        .first
+        .or(range.last)
+        .unwrap_or(DEFAULT_CHARACTER_PAGE_SIZE);
 
-    let records = sqlx::query!(
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let mut builder = QueryBuilder::new(
         "
             SELECT
-                Characters.Id, Race
+                Characters.Id as Id,
+                Characters.UserId as UserId,
+                Characters.Name as Name,
+                Characters.Nickname as Nickname,
+                Characters.Race as Race,
+                Characters.CreatedAt as CreatedAt,
+                Androids.Id as AndroidId,
+                Cyborgs.Id as CyborgId,
+                Humans.Id as HumanId
             FROM
                 Characters
-                    JOIN Users ON Characters.UserId = Users.Id
+                    LEFT JOIN Androids ON Characters.Id = Androids.Id
+                    LEFT JOIN Cyborgs ON Characters.Id = Cyborgs.Id
+                    LEFT JOIN Humans ON Characters.Id = Humans.Id
             WHERE
-                Users.Id = ?
-            ORDER BY
-                Characters.Id
         ",
-        user_id_str,
+    );
+    push_character_filter(&mut builder, user_id, filter);
+    if let Some(after) = range.after {
+        builder.push(" AND ");
+        push_keyset_condition(&mut builder, &sort_keys, after, false);
+    }
+    if let Some(before) = range.before {
+        builder.push(" AND ");
+        push_keyset_condition(&mut builder, &sort_keys, before, true);
+    }
+    builder.push(" ORDER BY ");
+    let mut separated = builder.separated(", ");
+    for sort_key in &sort_keys {
+        separated.push(sort_key.expression());
+        separated.push_unseparated(if sort_key.is_descending(backwards) {
+            " DESC"
+        } else {
+            " ASC"
+        });
+    }
+    // one more row than needed tells whether there are more rows in the direction of the paging
+    builder.push(" LIMIT ").push_bind(limit as i64 + 1);
+
+    let mut records = builder
+        .build_query_as::<SqlCharacter>()
+        .fetch_all(&mut *transaction)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let has_more = records.len() > limit;
+    records.truncate(limit);
+
+    // the rows before the starting cursor are the ones that are not after it in the direction of
+    // the paging
+    let starting_cursor = if backwards { range.before } else { range.after };
+    let has_rows_before_start = match starting_cursor {
+        Some(cursor) => {
+            let mut builder = QueryBuilder::new("SELECT EXISTS (SELECT 1 FROM Characters WHERE");
+            push_character_filter(&mut builder, user_id, filter);
+            builder.push(" AND NOT ");
+            push_keyset_condition(&mut builder, &sort_keys, cursor, backwards);
+            builder.push(")");
+
+            builder
+                .build_query_scalar::<bool>()
+                .fetch_one(&mut *transaction)
+                .await
+                .to_sqlx_error_result()
+                .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+        }
+        None => false,
+    };
+
+    let (mut has_previous_page, has_next_page) = if backwards {
+        records.reverse();
+        (has_more, has_rows_before_start)
+    } else {
+        (has_rows_before_start, has_more)
+    };
+
+    if let (Some(_), Some(last)) = (range.first, range.last) {
+        if records.len() > last {
+            records.drain(..records.len() - last);
+            has_previous_page = true;
+        }
+    }
+
+    let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM Characters WHERE");
+    push_character_filter(&mut builder, user_id, filter);
+
+    let total_count = builder
+        .build_query_scalar::<i64>()
+        .fetch_one(&mut *transaction)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let mut characters = Vec::with_capacity(records.len());
+
+    for record in records {
+        let cursor = CharacterCursor {
+            order: cursor_order.clone(),
+            values: sort_keys
+                .iter()
+                .map(|sort_key| sort_key.value_of(&record))
+                .collect(),
+        };
+
+        if let Some(owned_character) = record
+            .try_into_character(state)
+            .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+        {
+            characters.push((cursor, owned_character.character));
+        }
+    }
+
+    Ok(CharacterPage {
+        characters,
+        has_previous_page,
+        has_next_page,
+        total_count,
+    })
+}
+
+/// Loads the characters with the given ids regardless of the user they belong to, ids without a
+/// character are left out of the result
+pub async fn get_characters_by_ids(
+    state: &State,
+    character_ids: &[Id],
+) -> Result<HashMap<Id, OwnedCharacter>, GetCharactersError> {
+    let character_ids_json = ids_to_json_array(character_ids);
+
+    let records = sqlx::query_as!(
+        SqlCharacter,
+        r#"
+            SELECT
+                Characters.Id as Id,
+                Characters.UserId as UserId,
+                Characters.Name as Name,
+                Characters.Nickname as Nickname,
+                Characters.Race as Race,
+                Characters.CreatedAt as CreatedAt,
+                Androids.Id as "AndroidId?",
+                Cyborgs.Id as "CyborgId?",
+                Humans.Id as "HumanId?"
+            FROM
+                Characters
+                    LEFT JOIN Androids ON Characters.Id = Androids.Id
+                    LEFT JOIN Cyborgs ON Characters.Id = Cyborgs.Id
+                    LEFT JOIN Humans ON Characters.Id = Humans.Id
+            WHERE
+                Characters.Id IN (SELECT value FROM json_each(?))
+        "#,
+        character_ids_json,
     )
     .fetch_all(state.database.connection_pool_ref())
     .await
     .to_sqlx_error_result()
     .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-    let mut ret = Vec::new();
+    let mut ret = HashMap::new();
 
     for record in records {
-        let character_race = record.Race.parse()?;
-
-        let character = match character_race {
-            CharacterRace::Android => get_android_by_id(state, user_id, &record.Id.into())
-                .await?
-                .map(Character::Android),
-            CharacterRace::Cyborg => get_cyborg_by_id(state, user_id, &record.Id.into())
-                .await?
-                .map(Character::Cyborg),
-            CharacterRace::Human => get_human_by_id(state, user_id, &record.Id.into())
-                .await?
-                .map(Character::Human),
-        };
+        let character_id = record.Id.clone();
 
-        if let Some(character) = character {
-            ret.push(character);
+        if let Some(character) = record
+            .try_into_character(state)
+            .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+        {
+            ret.insert(character_id, character);
         }
     }
 
     Ok(ret)
 }
 
-pub mod error {
-    use crate::{
-        error::SqlxError,
-        model::enums::character_race::{error::InvalidCharacterRace, CharacterRace},
-        sql_queries::{
-            sql_android::error::GetAndroidByIdError, sql_cyborg::error::GetCyborgByIdError,
-            sql_human::error::GetHumanByIdError,
-        },
-    };
+pub async fn create_character(
+    state: &State,
+    user_id: &Id,
+    character_definition: &CharacterCreationInput,
+) -> Result<Character, CreateCharacterError> {
+    let user_id_str = user_id.as_string_ref();
+    let character_id: Id = uuid::Uuid::new_v4().to_string().into();
+    let character_id_str = character_id.as_string_ref();
+    let race_str: &str = character_definition.race.into();
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64;
 
-    #[derive(Clone, Debug, thiserror::Error)]
-    #[error("CharacterRaceMismatchError: stored = '{stored}', expected = '{expected}'")]
-    pub struct CharacterRaceMismatchError {
-        pub stored: CharacterRace,
-        pub expected: CharacterRace,
-    }
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-    #[derive(Clone, Debug, thiserror::Error)]
-    pub enum GetCharacterByIdError {
-        #[error("GetCharacterByIdError: '{0}'")]
-        SqlxError(
-            #[from]
-            #[source]
-            SqlxError,
-        ),
+    sqlx::query!(
+        "
+            SELECT
+                Id
+            FROM
+                Users
+            WHERE
+                Id = ?
+        ",
+        user_id_str,
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindUserById(user_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-        #[error("GetCharacterByIdError: '{0}'")]
-        InvalidCharacterRace(
-            #[from]
-            #[source]
-            InvalidCharacterRace,
-        ),
+    sqlx::query!(
+        "
+            INSERT INTO
+                Ids (Id, Type)
+            VALUES
+                (?, 'Character')
+        ",
+        character_id_str,
+    )
+    .execute(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-        #[error("GetCharacterByIdError: '{0}'")]
-        GetAndroidByIdError(
-            #[from]
-            #[source]
-            GetAndroidByIdError,
-        ),
+    sqlx::query!(
+        "
+            INSERT INTO
+                Characters (Id, UserId, Name, NickName, Race, CreatedAt)
+            VALUES
+                (?, ?, ?, ?, ?, ?)
+        ",
+        character_id_str,
+        user_id_str,
+        character_definition.name,
+        character_definition.nickname,
+        race_str,
+        created_at,
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref database_error) if database_error.is_unique_violation() => {
+            CreateCharacterError::from(CharacterNicknameAlreadyTaken {
+                user_id: user_id.clone(),
+                nickname: character_definition.nickname.clone(),
+            })
+        }
+        e => SqlxError::from(e).into(),
+    })
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-        #[error("GetCharacterByIdError: '{0}'")]
-        GetCyborgByIdError(
-            #[from]
-            #[source]
-            GetCyborgByIdError,
-        ),
+    insert_into_race_table(&mut transaction, character_definition.race, &character_id).await?;
 
-        #[error("GetCharacterByIdError: '{0}'")]
-        GetHumanByIdError(
-            #[from]
-            #[source]
-            GetHumanByIdError,
-        ),
+    let character = match character_definition.race {
+        CharacterRace::Android => {
+            fetch_android_by_id(&mut *transaction, state, user_id, &character_id)
+                .await?
+                .map(Character::Android)
+        }
+        CharacterRace::Cyborg => {
+            fetch_cyborg_by_id(&mut *transaction, state, user_id, &character_id)
+                .await?
+                .map(Character::Cyborg)
+        }
+        CharacterRace::Human => fetch_human_by_id(&mut *transaction, state, user_id, &character_id)
+            .await?
+            .map(Character::Human),
     }
+    .ok_or_else(|| CannotFindCreatedCharacter(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-    #[derive(Clone, Debug, thiserror::Error)]
-    pub enum GetCharactersError {
-        #[error("GetCharactersError: '{0}'")]
-        SqlxError(
-            #[from]
-            #[source]
-            SqlxError,
-        ),
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-        #[error("GetCharactersError: '{0}'")]
-        InvalidCharacterRace(
-            #[from]
-            #[source]
-            InvalidCharacterRace,
-        ),
+    Ok(character)
+}
 
-        #[error("GetCharactersError: '{0}'")]
-        GetAndroidByIdError(
-            #[from]
-            #[source]
-            GetAndroidByIdError,
-        ),
+pub async fn update_character(
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
+    patch: &CharacterPatchInput,
+) -> Result<Character, UpdateCharacterError> {
+    let user_id_str = user_id.as_string_ref();
+    let character_id_str = character_id.as_string_ref();
+    let name = patch.name.as_opt_deref::<str>();
+    let name_is_set = name.is_some();
+    let name = name.flatten();
 
-        #[error("GetCharactersError: '{0}'")]
-        GetCyborgByIdError(
-            #[from]
-            #[source]
-            GetCyborgByIdError,
-        ),
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
 
-        #[error("GetCharactersError: '{0}'")]
-        GetHumanByIdError(
-            #[from]
-            #[source]
-            GetHumanByIdError,
-        ),
+    let race: CharacterRace = sqlx::query!(
+        "
+            SELECT
+                Race
+            FROM
+                Characters
+            WHERE
+                Id = ? AND UserId = ?
+        ",
+        character_id_str,
+        user_id_str,
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindCharacterById(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .Race
+    .parse()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    sqlx::query!(
+        "
+            UPDATE
+                Characters
+            SET
+                NickName = COALESCE(?1, NickName),
+                Name = CASE WHEN ?2 THEN ?3 ELSE Name END
+            WHERE
+                Id = ?4
+        ",
+        patch.nickname,
+        name_is_set,
+        name,
+        character_id_str,
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref database_error) if database_error.is_unique_violation() => {
+            UpdateCharacterError::from(CharacterNicknameAlreadyTaken {
+                user_id: user_id.clone(),
+                nickname: patch.nickname.clone().unwrap_or_default(),
+            })
+        }
+        e => SqlxError::from(e).into(),
+    })
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let character = match race {
+        CharacterRace::Android => {
+            fetch_android_by_id(&mut *transaction, state, user_id, character_id)
+                .await?
+                .map(Character::Android)
+        }
+        CharacterRace::Cyborg => {
+            fetch_cyborg_by_id(&mut *transaction, state, user_id, character_id)
+                .await?
+                .map(Character::Cyborg)
+        }
+        CharacterRace::Human => fetch_human_by_id(&mut *transaction, state, user_id, character_id)
+            .await?
+            .map(Character::Human),
+    }
+    .ok_or_else(|| CannotFindUpdatedCharacter(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(character)
+}
+
+/// Deletes a character of the user together with its augmentations, returns the id of the
+/// deleted character
+pub async fn delete_character(
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
+) -> Result<Id, DeleteCharacterError> {
+    let user_id_str = user_id.as_string_ref();
+    let character_id_str = character_id.as_string_ref();
+
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let race: CharacterRace = sqlx::query!(
+        "
+            SELECT
+                Race
+            FROM
+                Characters
+            WHERE
+                Id = ? AND UserId = ?
+        ",
+        character_id_str,
+        user_id_str,
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindCharacterById(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .Race
+    .parse()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    delete_augmentations_of_character(&mut transaction, character_id).await?;
+
+    delete_from_race_table(&mut transaction, race, character_id).await?;
+
+    sqlx::query!(
+        "
+            DELETE FROM
+                Characters
+            WHERE
+                Id = ?
+        ",
+        character_id_str,
+    )
+    .execute(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    sqlx::query!(
+        "
+            DELETE FROM
+                Ids
+            WHERE
+                Id = ?
+        ",
+        character_id_str,
+    )
+    .execute(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(character_id.clone())
+}
+
+/// Moves a character of the user to another race table and updates its stored race
+///
+/// Augmentations that the new race cannot hold are either removed or make the conversion fail,
+/// depending on `incompatible_augmentations`.
+pub async fn convert_character_race(
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
+    new_race: CharacterRace,
+    incompatible_augmentations: IncompatibleAugmentationPolicy,
+) -> Result<Character, ConvertCharacterRaceError> {
+    let user_id_str = user_id.as_string_ref();
+    let character_id_str = character_id.as_string_ref();
+    let new_race_str: &str = new_race.into();
+
+    let mut transaction = state
+        .database
+        .connection_pool_ref()
+        .begin()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let race: CharacterRace = sqlx::query!(
+        "
+            SELECT
+                Race
+            FROM
+                Characters
+            WHERE
+                Id = ? AND UserId = ?
+        ",
+        character_id_str,
+        user_id_str,
+    )
+    .fetch_optional(&mut *transaction)
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindCharacterById(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .Race
+    .parse()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    if race != new_race {
+        let incompatible_augmentation_ids: Vec<Id> =
+            fetch_augmentations(&mut *transaction, state, character_id, None)
+                .await?
+                .into_iter()
+                .filter(|augmentation| !new_race.supports_augmentation_type(augmentation.r#type))
+                .map(|augmentation| augmentation.id)
+                .collect();
+
+        if !incompatible_augmentation_ids.is_empty() {
+            match incompatible_augmentations {
+                IncompatibleAugmentationPolicy::Drop => {
+                    for augmentation_id in &incompatible_augmentation_ids {
+                        delete_augmentation(&mut transaction, augmentation_id).await?;
+                    }
+                }
+                IncompatibleAugmentationPolicy::Fail => {
+                    return Err(IncompatibleAugmentations {
+                        race: new_race,
+                        augmentation_ids: incompatible_augmentation_ids,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        delete_from_race_table(&mut transaction, race, character_id).await?;
+
+        sqlx::query!(
+            "
+                UPDATE
+                    Characters
+                SET
+                    Race = ?
+                WHERE
+                    Id = ?
+            ",
+            new_race_str,
+            character_id_str,
+        )
+        .execute(&mut *transaction)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+        insert_into_race_table(&mut transaction, new_race, character_id).await?;
+    }
+
+    let character = match new_race {
+        CharacterRace::Android => {
+            fetch_android_by_id(&mut *transaction, state, user_id, character_id)
+                .await?
+                .map(Character::Android)
+        }
+        CharacterRace::Cyborg => {
+            fetch_cyborg_by_id(&mut *transaction, state, user_id, character_id)
+                .await?
+                .map(Character::Cyborg)
+        }
+        CharacterRace::Human => fetch_human_by_id(&mut *transaction, state, user_id, character_id)
+            .await?
+            .map(Character::Human),
+    }
+    .ok_or_else(|| CannotFindConvertedCharacter(character_id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    transaction
+        .commit()
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(character)
+}
+
+async fn insert_into_race_table(
+    connection: &mut SqliteConnection,
+    race: CharacterRace,
+    character_id: &Id,
+) -> Result<(), SqlxError> {
+    let character_id_str = character_id.as_string_ref();
+
+    let query = match race {
+        CharacterRace::Android => sqlx::query!(
+            "
+                INSERT INTO
+                    Androids (Id)
+                VALUES
+                    (?)
+            ",
+            character_id_str,
+        ),
+        CharacterRace::Cyborg => sqlx::query!(
+            "
+                INSERT INTO
+                    Cyborgs (Id)
+                VALUES
+                    (?)
+            ",
+            character_id_str,
+        ),
+        CharacterRace::Human => sqlx::query!(
+            "
+                INSERT INTO
+                    Humans (Id)
+                VALUES
+                    (?)
+            ",
+            character_id_str,
+        ),
+    };
+
+    query
+        .execute(&mut *connection)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(())
+}
+
+async fn delete_from_race_table(
+    connection: &mut SqliteConnection,
+    race: CharacterRace,
+    character_id: &Id,
+) -> Result<(), SqlxError> {
+    let character_id_str = character_id.as_string_ref();
+
+    let query = match race {
+        CharacterRace::Android => sqlx::query!(
+            "
+                DELETE FROM
+                    Androids
+                WHERE
+                    Id = ?
+            ",
+            character_id_str,
+        ),
+        CharacterRace::Cyborg => sqlx::query!(
+            "
+                DELETE FROM
+                    Cyborgs
+                WHERE
+                    Id = ?
+            ",
+            character_id_str,
+        ),
+        CharacterRace::Human => sqlx::query!(
+            "
+                DELETE FROM
+                    Humans
+                WHERE
+                    Id = ?
+            ",
+            character_id_str,
+        ),
+    };
+
+    query
+        .execute(&mut *connection)
+        .await
+        .to_sqlx_error_result()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(())
+}
+
+/// Used by the queries that are built at runtime, the columns are selected by their names
+impl FromRow<'_, SqliteRow> for SqlCharacter {
+    fn from_row(row: &SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            Id: row.try_get::<String, _>("Id")?.into(),
+            UserId: row.try_get::<String, _>("UserId")?.into(),
+            Name: row.try_get("Name")?,
+            Nickname: row.try_get("Nickname")?,
+            Race: row.try_get("Race")?,
+            CreatedAt: row.try_get("CreatedAt")?,
+            AndroidId: row.try_get("AndroidId")?,
+            CyborgId: row.try_get("CyborgId")?,
+            HumanId: row.try_get("HumanId")?,
+        })
+    }
+}
+
+impl SqlCharacter {
+    /// Characters whose race table row is missing are converted to `None`, like with the by-id
+    /// lookups
+    pub fn try_into_character(
+        self,
+        state: &State,
+    ) -> Result<Option<OwnedCharacter>, CannotConvertSqlCharacterToCharacter> {
+        let character = match self.Race.parse()? {
+            CharacterRace::Android => self
+                .AndroidId
+                .map(|_| {
+                    SqlAndroid {
+                        Id: self.Id,
+                        Name: self.Name,
+                        Nickname: self.Nickname,
+                        Race: self.Race,
+                    }
+                    .try_into_android(state)
+                })
+                .transpose()?
+                .map(Character::Android),
+            CharacterRace::Cyborg => self
+                .CyborgId
+                .map(|_| {
+                    SqlCyborg {
+                        Id: self.Id,
+                        Name: self.Name,
+                        Nickname: self.Nickname,
+                        Race: self.Race,
+                    }
+                    .try_into_cyborg(state)
+                })
+                .transpose()?
+                .map(Character::Cyborg),
+            CharacterRace::Human => self
+                .HumanId
+                .map(|_| {
+                    SqlHuman {
+                        Id: self.Id,
+                        Name: self.Name,
+                        Nickname: self.Nickname,
+                        Race: self.Race,
+                    }
+                    .try_into_human(state)
+                })
+                .transpose()?
+                .map(Character::Human),
+        };
+
+        Ok(character.map(|character| OwnedCharacter {
+            user_id: self.UserId,
+            character,
+        }))
+    }
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, InputType, Value};
+
+    use crate::{
+        error::{ErrorCode, SqlxError, INTERNAL_ERROR_CODE},
+        model::{
+            enums::character_race::{error::InvalidCharacterRace, CharacterRace},
+            scalars::id::Id,
+        },
+        sql_queries::{
+            sql_android::error::{CannotConvertSqlAndroidToAndroid, GetAndroidByIdError},
+            sql_augmentation::error::GetAugmentationsError,
+            sql_cyborg::error::{CannotConvertSqlCyborgToCyborg, GetCyborgByIdError},
+            sql_human::error::{CannotConvertSqlHumanToHuman, GetHumanByIdError},
+            sql_user::error::CannotFindUserById,
+        },
+    };
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CharacterRaceMismatchError: stored = '{stored}', expected = '{expected}'")]
+    pub struct CharacterRaceMismatchError {
+        pub stored: CharacterRace,
+        pub expected: CharacterRace,
+    }
+
+    impl ErrorCode for CharacterRaceMismatchError {
+        fn code(&self) -> &'static str {
+            "RACE_MISMATCH"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("stored", self.stored.to_value()),
+                ("expected", self.expected.to_value()),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetCharacterByIdError {
+        #[error("GetCharacterByIdError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetCharacterByIdError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("GetCharacterByIdError: '{0}'")]
+        GetAndroidByIdError(
+            #[from]
+            #[source]
+            GetAndroidByIdError,
+        ),
+
+        #[error("GetCharacterByIdError: '{0}'")]
+        GetCyborgByIdError(
+            #[from]
+            #[source]
+            GetCyborgByIdError,
+        ),
+
+        #[error("GetCharacterByIdError: '{0}'")]
+        GetHumanByIdError(
+            #[from]
+            #[source]
+            GetHumanByIdError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum CannotConvertSqlCharacterToCharacter {
+        #[error("CannotConvertSqlCharacterToCharacter: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("CannotConvertSqlCharacterToCharacter: '{0}'")]
+        CannotConvertSqlAndroidToAndroid(
+            #[from]
+            #[source]
+            CannotConvertSqlAndroidToAndroid,
+        ),
+
+        #[error("CannotConvertSqlCharacterToCharacter: '{0}'")]
+        CannotConvertSqlCyborgToCyborg(
+            #[from]
+            #[source]
+            CannotConvertSqlCyborgToCyborg,
+        ),
+
+        #[error("CannotConvertSqlCharacterToCharacter: '{0}'")]
+        CannotConvertSqlHumanToHuman(
+            #[from]
+            #[source]
+            CannotConvertSqlHumanToHuman,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetCharactersError {
+        #[error("GetCharactersError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetCharactersError: '{0}'")]
+        CannotConvertSqlCharacterToCharacter(
+            #[from]
+            #[source]
+            CannotConvertSqlCharacterToCharacter,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CharacterNicknameAlreadyTaken: user_id = '{user_id:?}', nickname = '{nickname}'")]
+    pub struct CharacterNicknameAlreadyTaken {
+        pub user_id: Id,
+        pub nickname: String,
+    }
+
+    impl ErrorCode for CharacterNicknameAlreadyTaken {
+        fn code(&self) -> &'static str {
+            "CHARACTER_NICKNAME_TAKEN"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("userId", self.user_id.to_value()),
+                ("nickname", Value::from(self.nickname.as_str())),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CannotFindCharacterById: character_id = '{0:?}'")]
+    pub struct CannotFindCharacterById(pub Id);
+
+    impl ErrorCode for CannotFindCharacterById {
+        fn code(&self) -> &'static str {
+            "CHARACTER_NOT_FOUND"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![("characterId", self.0.to_value())]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CannotFindCreatedCharacter: character_id = '{0:?}'")]
+    pub struct CannotFindCreatedCharacter(pub Id);
+
+    impl ErrorCode for CannotFindCreatedCharacter {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CannotFindUpdatedCharacter: character_id = '{0:?}'")]
+    pub struct CannotFindUpdatedCharacter(pub Id);
+
+    impl ErrorCode for CannotFindUpdatedCharacter {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CannotFindConvertedCharacter: character_id = '{0:?}'")]
+    pub struct CannotFindConvertedCharacter(pub Id);
+
+    impl ErrorCode for CannotFindConvertedCharacter {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error(
+        "IncompatibleAugmentations: race = '{race}', augmentation_ids = '{augmentation_ids:?}'"
+    )]
+    pub struct IncompatibleAugmentations {
+        pub race: CharacterRace,
+        pub augmentation_ids: Vec<Id>,
+    }
+
+    impl ErrorCode for IncompatibleAugmentations {
+        fn code(&self) -> &'static str {
+            "INCOMPATIBLE_AUGMENTATIONS"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("race", self.race.to_value()),
+                ("augmentationIds", self.augmentation_ids.to_value()),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("CharacterPageSizeTooLarge: requested = '{requested}', max = '{max}'")]
+    pub struct CharacterPageSizeTooLarge {
+        pub requested: usize,
+        pub max: usize,
+    }
+
+    impl ErrorCode for CharacterPageSizeTooLarge {
+        fn code(&self) -> &'static str {
+            "PAGE_SIZE_TOO_LARGE"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("requested", Value::from(self.requested)),
+                ("max", Value::from(self.max)),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("InvalidCharacterCursor: the cursor was not created with the requested order")]
+    pub struct InvalidCharacterCursor;
+
+    impl ErrorCode for InvalidCharacterCursor {
+        fn code(&self) -> &'static str {
+            "INVALID_CURSOR"
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum CreateCharacterError {
+        #[error("CreateCharacterError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        CannotFindUserById(
+            #[from]
+            #[source]
+            CannotFindUserById,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        CharacterNicknameAlreadyTaken(
+            #[from]
+            #[source]
+            CharacterNicknameAlreadyTaken,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        CannotFindCreatedCharacter(
+            #[from]
+            #[source]
+            CannotFindCreatedCharacter,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        GetAndroidByIdError(
+            #[from]
+            #[source]
+            GetAndroidByIdError,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        GetCyborgByIdError(
+            #[from]
+            #[source]
+            GetCyborgByIdError,
+        ),
+
+        #[error("CreateCharacterError: '{0}'")]
+        GetHumanByIdError(
+            #[from]
+            #[source]
+            GetHumanByIdError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum UpdateCharacterError {
+        #[error("UpdateCharacterError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        CannotFindCharacterById(
+            #[from]
+            #[source]
+            CannotFindCharacterById,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        CharacterNicknameAlreadyTaken(
+            #[from]
+            #[source]
+            CharacterNicknameAlreadyTaken,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        CannotFindUpdatedCharacter(
+            #[from]
+            #[source]
+            CannotFindUpdatedCharacter,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        GetAndroidByIdError(
+            #[from]
+            #[source]
+            GetAndroidByIdError,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        GetCyborgByIdError(
+            #[from]
+            #[source]
+            GetCyborgByIdError,
+        ),
+
+        #[error("UpdateCharacterError: '{0}'")]
+        GetHumanByIdError(
+            #[from]
+            #[source]
+            GetHumanByIdError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum DeleteCharacterError {
+        #[error("DeleteCharacterError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("DeleteCharacterError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("DeleteCharacterError: '{0}'")]
+        CannotFindCharacterById(
+            #[from]
+            #[source]
+            CannotFindCharacterById,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum ConvertCharacterRaceError {
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        InvalidCharacterRace(
+            #[from]
+            #[source]
+            InvalidCharacterRace,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        CannotFindCharacterById(
+            #[from]
+            #[source]
+            CannotFindCharacterById,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        GetAugmentationsError(
+            #[from]
+            #[source]
+            GetAugmentationsError,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        IncompatibleAugmentations(
+            #[from]
+            #[source]
+            IncompatibleAugmentations,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        CannotFindConvertedCharacter(
+            #[from]
+            #[source]
+            CannotFindConvertedCharacter,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        GetAndroidByIdError(
+            #[from]
+            #[source]
+            GetAndroidByIdError,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        GetCyborgByIdError(
+            #[from]
+            #[source]
+            GetCyborgByIdError,
+        ),
+
+        #[error("ConvertCharacterRaceError: '{0}'")]
+        GetHumanByIdError(
+            #[from]
+            #[source]
+            GetHumanByIdError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetCharactersPageError {
+        #[error("GetCharactersPageError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetCharactersPageError: '{0}'")]
+        CharacterPageSizeTooLarge(
+            #[from]
+            #[source]
+            CharacterPageSizeTooLarge,
+        ),
+
+        #[error("GetCharactersPageError: '{0}'")]
+        InvalidCharacterCursor(
+            #[from]
+            #[source]
+            InvalidCharacterCursor,
+        ),
+
+        #[error("GetCharactersPageError: '{0}'")]
+        CannotConvertSqlCharacterToCharacter(
+            #[from]
+            #[source]
+            CannotConvertSqlCharacterToCharacter,
+        ),
+    }
+
+    impl ErrorCode for GetCharacterByIdError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::GetAndroidByIdError(e) => e.code(),
+                Self::GetCyborgByIdError(e) => e.code(),
+                Self::GetHumanByIdError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::GetAndroidByIdError(e) => e.fields(),
+                Self::GetCyborgByIdError(e) => e.fields(),
+                Self::GetHumanByIdError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetCharacterByIdError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for CannotConvertSqlCharacterToCharacter {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotConvertSqlAndroidToAndroid(e) => e.code(),
+                Self::CannotConvertSqlCyborgToCyborg(e) => e.code(),
+                Self::CannotConvertSqlHumanToHuman(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotConvertSqlAndroidToAndroid(e) => e.fields(),
+                Self::CannotConvertSqlCyborgToCyborg(e) => e.fields(),
+                Self::CannotConvertSqlHumanToHuman(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetCharactersError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotConvertSqlCharacterToCharacter(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotConvertSqlCharacterToCharacter(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetCharactersError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for CreateCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotFindUserById(e) => e.code(),
+                Self::CharacterNicknameAlreadyTaken(e) => e.code(),
+                Self::CannotFindCreatedCharacter(e) => e.code(),
+                Self::GetAndroidByIdError(e) => e.code(),
+                Self::GetCyborgByIdError(e) => e.code(),
+                Self::GetHumanByIdError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotFindUserById(e) => e.fields(),
+                Self::CharacterNicknameAlreadyTaken(e) => e.fields(),
+                Self::CannotFindCreatedCharacter(e) => e.fields(),
+                Self::GetAndroidByIdError(e) => e.fields(),
+                Self::GetCyborgByIdError(e) => e.fields(),
+                Self::GetHumanByIdError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for UpdateCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotFindCharacterById(e) => e.code(),
+                Self::CharacterNicknameAlreadyTaken(e) => e.code(),
+                Self::CannotFindUpdatedCharacter(e) => e.code(),
+                Self::GetAndroidByIdError(e) => e.code(),
+                Self::GetCyborgByIdError(e) => e.code(),
+                Self::GetHumanByIdError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotFindCharacterById(e) => e.fields(),
+                Self::CharacterNicknameAlreadyTaken(e) => e.fields(),
+                Self::CannotFindUpdatedCharacter(e) => e.fields(),
+                Self::GetAndroidByIdError(e) => e.fields(),
+                Self::GetCyborgByIdError(e) => e.fields(),
+                Self::GetHumanByIdError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for UpdateCharacterError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for DeleteCharacterError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotFindCharacterById(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotFindCharacterById(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for DeleteCharacterError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for ConvertCharacterRaceError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CannotFindCharacterById(e) => e.code(),
+                Self::GetAugmentationsError(e) => e.code(),
+                Self::IncompatibleAugmentations(e) => e.code(),
+                Self::CannotFindConvertedCharacter(e) => e.code(),
+                Self::GetAndroidByIdError(e) => e.code(),
+                Self::GetCyborgByIdError(e) => e.code(),
+                Self::GetHumanByIdError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CannotFindCharacterById(e) => e.fields(),
+                Self::GetAugmentationsError(e) => e.fields(),
+                Self::IncompatibleAugmentations(e) => e.fields(),
+                Self::CannotFindConvertedCharacter(e) => e.fields(),
+                Self::GetAndroidByIdError(e) => e.fields(),
+                Self::GetCyborgByIdError(e) => e.fields(),
+                Self::GetHumanByIdError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for ConvertCharacterRaceError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for GetCharactersPageError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CharacterPageSizeTooLarge(e) => e.code(),
+                Self::InvalidCharacterCursor(e) => e.code(),
+                Self::CannotConvertSqlCharacterToCharacter(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CharacterPageSizeTooLarge(e) => e.fields(),
+                Self::InvalidCharacterCursor(e) => e.fields(),
+                Self::CannotConvertSqlCharacterToCharacter(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetCharactersPageError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_graphql::connection::{CursorType, OpaqueCursor};
+
+    use super::*;
+
+    fn cursor() -> CharacterCursor {
+        CharacterCursor {
+            order: vec![
+                (CharacterOrderField::Name, OrderDirection::Desc),
+                (CharacterOrderField::CreatedAt, OrderDirection::Asc),
+            ],
+            values: vec![
+                CharacterSortValue::Text("Adam Jensen".into()),
+                CharacterSortValue::Integer(1_700_000_000),
+                CharacterSortValue::Text("Character:8d5b1f2e-6b0a-4c8e-9f3a-2d7c4b1e0a95".into()),
+            ],
+        }
+    }
+
+    fn decode(encoded: &str) -> Option<CharacterCursor> {
+        OpaqueCursor::<CharacterCursor>::decode_cursor(encoded)
+            .ok()
+            .map(|cursor| cursor.0)
+    }
+
+    fn encode(value: &serde_json::Value) -> String {
+        OpaqueCursor(value.clone()).encode_cursor()
+    }
+
+    #[test]
+    fn cursor_survives_encoding_and_decoding() {
+        let encoded = OpaqueCursor(cursor()).encode_cursor();
+
+        let decoded = decode(&encoded).expect("decodable cursor");
+        assert_eq!(decoded, cursor());
+        assert!(decoded.is_created_with(&cursor().order));
+    }
+
+    #[test]
+    fn cursor_is_rejected_with_another_order() {
+        let cursor = cursor();
+
+        assert!(!cursor.is_created_with(&[]));
+        assert!(!cursor.is_created_with(&cursor.order[..1]));
+        assert!(!cursor.is_created_with(&[
+            (CharacterOrderField::Name, OrderDirection::Asc),
+            (CharacterOrderField::CreatedAt, OrderDirection::Asc),
+        ]));
+        assert!(!cursor.is_created_with(&[
+            (CharacterOrderField::Nickname, OrderDirection::Desc),
+            (CharacterOrderField::CreatedAt, OrderDirection::Asc),
+        ]));
+    }
+
+    #[test]
+    fn tampered_cursor_is_rejected() {
+        let order = cursor().order;
+        let mut value = serde_json::to_value(cursor()).expect("serializable cursor");
+
+        // a value is removed, the order is kept
+        value["values"]
+            .as_array_mut()
+            .expect("array of values")
+            .pop();
+        let decoded = decode(&encode(&value)).expect("decodable cursor");
+        assert!(!decoded.is_created_with(&order));
+
+        // the order is changed to skip the keyset condition of a sort key
+        let mut value = serde_json::to_value(cursor()).expect("serializable cursor");
+        value["order"][0][1] = "Asc".into();
+        let decoded = decode(&encode(&value)).expect("decodable cursor");
+        assert!(!decoded.is_created_with(&order));
+
+        // unknown fields of the order cannot be decoded
+        let mut value = serde_json::to_value(cursor()).expect("serializable cursor");
+        value["order"][0][0] = "PASSWORD".into();
+        assert!(decode(&encode(&value)).is_none());
+    }
+
+    #[test]
+    fn garbage_is_not_a_cursor() {
+        let encoded = OpaqueCursor(cursor()).encode_cursor();
+
+        assert!(decode("").is_none());
+        assert!(decode("not a cursor").is_none());
+        assert!(decode(&encoded[..encoded.len() / 2]).is_none());
+        assert!(decode(&encode(&serde_json::json!({ "values": [] }))).is_none());
+        assert!(decode(&encode(
+            &serde_json::json!({ "order": [], "values": [1.5] })
+        ))
+        .is_none());
     }
 }
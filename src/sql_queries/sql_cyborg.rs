@@ -1,5 +1,7 @@
 use error::{CannotConvertSqlCyborgToCyborg, GetCyborgByIdError};
 
+use sqlx::SqliteExecutor;
+
 use crate::{
     error::SqlxErrorConverter,
     log_location,
@@ -22,6 +24,21 @@ pub async fn get_cyborg_by_id(
     state: &State,
     user_id: &Id,
     character_id: &Id,
+) -> Result<Option<Cyborg>, GetCyborgByIdError> {
+    fetch_cyborg_by_id(
+        state.database.connection_pool_ref(),
+        state,
+        user_id,
+        character_id,
+    )
+    .await
+}
+
+pub(super) async fn fetch_cyborg_by_id(
+    executor: impl SqliteExecutor<'_>,
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
 ) -> Result<Option<Cyborg>, GetCyborgByIdError> {
     let user_id_str = user_id.as_string_ref();
     let character_id_str = character_id.as_string_ref();
@@ -43,7 +60,7 @@ pub async fn get_cyborg_by_id(
         user_id_str,
         character_id_str,
     )
-    .fetch_optional(state.database.connection_pool_ref())
+    .fetch_optional(executor)
     .await
     .to_sqlx_error_result()
     .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
@@ -55,13 +72,14 @@ pub async fn get_cyborg_by_id(
 }
 
 impl SqlCyborg {
-    pub fn try_into_cyborg(self, _state: &State) -> Result<Cyborg, CannotConvertSqlCyborgToCyborg> {
+    pub fn try_into_cyborg(self, state: &State) -> Result<Cyborg, CannotConvertSqlCyborgToCyborg> {
         const EXPECTED_CHARACTER_RACE: CharacterRace = CharacterRace::Cyborg;
 
         let character_race = self.Race.parse()?;
 
         match character_race {
             EXPECTED_CHARACTER_RACE => Ok(Cyborg {
+                state: state.clone(),
                 id: self.Id,
                 name: self.Name,
                 nickname: self.Nickname,
@@ -77,8 +95,11 @@ impl SqlCyborg {
 }
 
 pub mod error {
+    use async_graphql::Value;
+
     use crate::{
-        error::SqlxError, model::enums::character_race::error::InvalidCharacterRace,
+        error::{ErrorCode, SqlxError},
+        model::enums::character_race::error::InvalidCharacterRace,
         sql_queries::sql_character::error::CharacterRaceMismatchError,
     };
 
@@ -122,4 +143,38 @@ pub mod error {
             CannotConvertSqlCyborgToCyborg,
         ),
     }
+
+    impl ErrorCode for CannotConvertSqlCyborgToCyborg {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CharacterRaceMismatchError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CharacterRaceMismatchError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetCyborgByIdError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotConvertSqlCyborgToCyborg(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotConvertSqlCyborgToCyborg(e) => e.fields(),
+            }
+        }
+    }
 }
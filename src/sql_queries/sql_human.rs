@@ -1,5 +1,7 @@
 use error::{CannotConvertSqlHumanToHuman, GetHumanByIdError};
 
+use sqlx::SqliteExecutor;
+
 use crate::{
     error::SqlxErrorConverter,
     log_location,
@@ -22,6 +24,21 @@ pub async fn get_human_by_id(
     state: &State,
     user_id: &Id,
     character_id: &Id,
+) -> Result<Option<Human>, GetHumanByIdError> {
+    fetch_human_by_id(
+        state.database.connection_pool_ref(),
+        state,
+        user_id,
+        character_id,
+    )
+    .await
+}
+
+pub(super) async fn fetch_human_by_id(
+    executor: impl SqliteExecutor<'_>,
+    state: &State,
+    user_id: &Id,
+    character_id: &Id,
 ) -> Result<Option<Human>, GetHumanByIdError> {
     let user_id_str = user_id.as_string_ref();
     let character_id_str = character_id.as_string_ref();
@@ -43,7 +60,7 @@ pub async fn get_human_by_id(
         user_id_str,
         character_id_str,
     )
-    .fetch_optional(state.database.connection_pool_ref())
+    .fetch_optional(executor)
     .await
     .to_sqlx_error_result()
     .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
@@ -77,8 +94,11 @@ impl SqlHuman {
 }
 
 pub mod error {
+    use async_graphql::Value;
+
     use crate::{
-        error::SqlxError, model::enums::character_race::error::InvalidCharacterRace,
+        error::{ErrorCode, SqlxError},
+        model::enums::character_race::error::InvalidCharacterRace,
         sql_queries::sql_character::error::CharacterRaceMismatchError,
     };
 
@@ -122,4 +142,38 @@ pub mod error {
             CannotConvertSqlHumanToHuman,
         ),
     }
+
+    impl ErrorCode for CannotConvertSqlHumanToHuman {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidCharacterRace(e) => e.code(),
+                Self::CharacterRaceMismatchError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidCharacterRace(e) => e.fields(),
+                Self::CharacterRaceMismatchError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetHumanByIdError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotConvertSqlHumanToHuman(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotConvertSqlHumanToHuman(e) => e.fields(),
+            }
+        }
+    }
 }
@@ -0,0 +1,113 @@
+use std::{collections::HashMap, str::FromStr};
+
+use error::{GetEntityTypesError, InvalidEntityType};
+
+use crate::{error::SqlxErrorConverter, log_location, model::scalars::id::Id, state::State};
+
+use super::ids_to_json_array;
+
+/// Kind of the entity an id belongs to, stored in `Ids.Type`
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EntityType {
+    User,
+    Character,
+    Augmentation,
+}
+
+impl FromStr for EntityType {
+    type Err = InvalidEntityType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "User" => Ok(EntityType::User),
+            "Character" => Ok(EntityType::Character),
+            "Augmentation" => Ok(EntityType::Augmentation),
+            _ => Err(InvalidEntityType(s.into())),
+        }
+    }
+}
+
+/// Looks up the kind of entity of every id, unknown ids are left out of the result
+pub async fn get_entity_types(
+    state: &State,
+    ids: &[Id],
+) -> Result<HashMap<Id, EntityType>, GetEntityTypesError> {
+    let ids_json = ids_to_json_array(ids);
+
+    let records = sqlx::query!(
+        "
+            SELECT
+                Id, Type
+            FROM
+                Ids
+            WHERE
+                Id IN (SELECT value FROM json_each(?))
+        ",
+        ids_json,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| Ok((record.Id.into(), record.Type.parse()?)))
+        .collect::<Result<_, InvalidEntityType>>()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?)
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::error::{ErrorCode, SqlxError, INTERNAL_ERROR_CODE};
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("InvalidEntityType: '{0}'")]
+    pub struct InvalidEntityType(pub String);
+
+    impl ErrorCode for InvalidEntityType {
+        fn code(&self) -> &'static str {
+            INTERNAL_ERROR_CODE
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetEntityTypesError {
+        #[error("GetEntityTypesError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetEntityTypesError: '{0}'")]
+        InvalidEntityType(
+            #[from]
+            #[source]
+            InvalidEntityType,
+        ),
+    }
+
+    impl ErrorCode for GetEntityTypesError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidEntityType(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidEntityType(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetEntityTypesError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+}
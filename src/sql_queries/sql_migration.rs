@@ -0,0 +1,55 @@
+use error::GetMigrationVersionError;
+
+use crate::{error::SqlxErrorConverter, log_location, state::State};
+
+/// Version of the last successfully applied migration, `None` when no migration has been applied
+pub async fn get_applied_migration_version(
+    state: &State,
+) -> Result<Option<i64>, GetMigrationVersionError> {
+    let record = sqlx::query!(
+        "
+            SELECT
+                MAX(version) AS Version
+            FROM
+                _sqlx_migrations
+            WHERE
+                success = 1
+        ",
+    )
+    .fetch_one(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(record.Version)
+}
+
+pub mod error {
+    use async_graphql::Value;
+
+    use crate::error::{ErrorCode, SqlxError};
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetMigrationVersionError {
+        #[error("GetMigrationVersionError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+    }
+
+    impl ErrorCode for GetMigrationVersionError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+            }
+        }
+    }
+}
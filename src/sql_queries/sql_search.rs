@@ -0,0 +1,314 @@
+use error::{SearchError, SearchLimitTooLarge};
+
+use crate::{
+    error::SqlxErrorConverter,
+    log_location,
+    model::{
+        scalars::id::Id,
+        types::{
+            augmentation_search_hit::AugmentationSearchHit,
+            character_search_hit::CharacterSearchHit,
+        },
+        unions::search_hit::SearchHit,
+    },
+    state::State,
+};
+
+use super::{sql_augmentation::SqlAugmentation, sql_character::get_characters_by_ids};
+
+/// Number of hits returned when the number of hits is not given
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+
+/// Largest number of hits that can be requested
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Wraps the matched terms in the snippets of the hits
+const SNIPPET_MATCH_START: &str = "<mark>";
+const SNIPPET_MATCH_END: &str = "</mark>";
+
+/// Delimit the matched terms in the snippets built by SQLite, they are replaced with the `<mark>`
+/// tags after the text is escaped, private use characters, so they do not occur in regular text
+const SNIPPET_MATCH_START_SENTINEL: char = '\u{E000}';
+const SNIPPET_MATCH_END_SENTINEL: char = '\u{E001}';
+
+/// Maximum number of tokens in a snippet
+const SNIPPET_TOKEN_COUNT: i64 = 12;
+
+#[allow(non_snake_case)]
+struct SqlCharacterSearchHit {
+    Id: Id,
+    Snippet: String,
+    Rank: f64,
+}
+
+#[allow(non_snake_case)]
+struct SqlAugmentationSearchHit {
+    Id: Id,
+    Name: String,
+    Description: String,
+    Type: String,
+    Snippet: String,
+    Rank: f64,
+}
+
+/// Converts the text typed by the player into an FTS5 query
+///
+/// Every word is quoted, so the FTS5 operators and special characters of the text are matched
+/// literally, and is matched as a prefix. A hit has to match every word. Returns `None` when the
+/// text has no words.
+fn to_match_query(text: &str) -> Option<String> {
+    let words: Vec<_> = text
+        .split_whitespace()
+        .map(|word| format!("\"{}\"*", word.replace('"', "\"\"")))
+        .collect();
+
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+/// Escapes the stored text of a snippet as HTML and wraps its matched terms in `<mark>` tags, so
+/// the snippet can be rendered as HTML
+fn to_html_snippet(snippet: &str) -> String {
+    let mut html = String::with_capacity(snippet.len());
+
+    for c in snippet.chars() {
+        match c {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            SNIPPET_MATCH_START_SENTINEL => html.push_str(SNIPPET_MATCH_START),
+            SNIPPET_MATCH_END_SENTINEL => html.push_str(SNIPPET_MATCH_END),
+            c => html.push(c),
+        }
+    }
+
+    html
+}
+
+/// Searches the nicknames and names of the characters of the user, and the names and
+/// descriptions of their augmentations
+///
+/// The hits of both kinds are ranked together by their bm25 scores, the best hit comes first.
+pub async fn search(
+    state: &State,
+    user_id: &Id,
+    text: &str,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, SearchError> {
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT);
+    if limit > MAX_SEARCH_LIMIT {
+        return Err(SearchLimitTooLarge {
+            requested: limit,
+            max: MAX_SEARCH_LIMIT,
+        }
+        .into());
+    }
+
+    let Some(match_query) = to_match_query(text) else {
+        return Ok(Vec::new());
+    };
+
+    let match_start = SNIPPET_MATCH_START_SENTINEL.to_string();
+    let match_end = SNIPPET_MATCH_END_SENTINEL.to_string();
+    let user_id_str = user_id.as_string_ref();
+    let limit_i64 = limit as i64;
+
+    let character_records = sqlx::query_as!(
+        SqlCharacterSearchHit,
+        r#"
+            SELECT
+                CharactersSearch.Id as "Id!: String",
+                snippet(CharactersSearch, -1, ?1, ?2, '…', ?3) as "Snippet!: String",
+                bm25(CharactersSearch) as "Rank!: f64"
+            FROM
+                CharactersSearch
+                    JOIN Characters ON CharactersSearch.Id = Characters.Id
+            WHERE
+                CharactersSearch MATCH ?4 AND Characters.UserId = ?5
+            ORDER BY
+                bm25(CharactersSearch)
+            LIMIT
+                ?6
+        "#,
+        match_start,
+        match_end,
+        SNIPPET_TOKEN_COUNT,
+        match_query,
+        user_id_str,
+        limit_i64,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let augmentation_records = sqlx::query_as!(
+        SqlAugmentationSearchHit,
+        r#"
+            SELECT
+                Augmentations.Id as "Id!: String",
+                Augmentations.Name as "Name!: String",
+                Augmentations.Description as "Description!: String",
+                Augmentations.Type as "Type!: String",
+                snippet(AugmentationsSearch, -1, ?1, ?2, '…', ?3) as "Snippet!: String",
+                bm25(AugmentationsSearch) as "Rank!: f64"
+            FROM
+                AugmentationsSearch
+                    JOIN Augmentations ON AugmentationsSearch.Id = Augmentations.Id
+                    JOIN Characters ON Augmentations.CharacterId = Characters.Id
+            WHERE
+                AugmentationsSearch MATCH ?4 AND Characters.UserId = ?5
+            ORDER BY
+                bm25(AugmentationsSearch)
+            LIMIT
+                ?6
+        "#,
+        match_start,
+        match_end,
+        SNIPPET_TOKEN_COUNT,
+        match_query,
+        user_id_str,
+        limit_i64,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let character_ids: Vec<_> = character_records
+        .iter()
+        .map(|record| record.Id.clone())
+        .collect();
+    let mut characters = get_characters_by_ids(state, &character_ids).await?;
+
+    // bm25 scores are negative, the lower the score the better the hit
+    let mut ranked_hits = Vec::with_capacity(character_records.len() + augmentation_records.len());
+
+    for record in character_records {
+        if let Some(owned_character) = characters.remove(&record.Id) {
+            ranked_hits.push((
+                record.Rank,
+                SearchHit::Character(CharacterSearchHit {
+                    character: owned_character.character,
+                    snippet: to_html_snippet(&record.Snippet),
+                    score: -record.Rank,
+                }),
+            ));
+        }
+    }
+
+    for record in augmentation_records {
+        let augmentation = SqlAugmentation {
+            Id: record.Id,
+            Name: record.Name,
+            Description: record.Description,
+            Type: record.Type,
+        }
+        .try_into_augmentation(state)
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+        ranked_hits.push((
+            record.Rank,
+            SearchHit::Augmentation(AugmentationSearchHit {
+                augmentation,
+                snippet: to_html_snippet(&record.Snippet),
+                score: -record.Rank,
+            }),
+        ));
+    }
+
+    ranked_hits.sort_by(|(lhs, _), (rhs, _)| lhs.total_cmp(rhs));
+    ranked_hits.truncate(limit);
+
+    Ok(ranked_hits.into_iter().map(|(_, hit)| hit).collect())
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::{
+        error::{ErrorCode, SqlxError},
+        sql_queries::{
+            sql_augmentation::error::CannotConvertSqlAugmentationToAugmentation,
+            sql_character::error::GetCharactersError,
+        },
+    };
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("SearchLimitTooLarge: requested = '{requested}', max = '{max}'")]
+    pub struct SearchLimitTooLarge {
+        pub requested: usize,
+        pub max: usize,
+    }
+
+    impl ErrorCode for SearchLimitTooLarge {
+        fn code(&self) -> &'static str {
+            "PAGE_SIZE_TOO_LARGE"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![
+                ("requested", Value::from(self.requested)),
+                ("max", Value::from(self.max)),
+            ]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum SearchError {
+        #[error("SearchError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("SearchError: '{0}'")]
+        SearchLimitTooLarge(
+            #[from]
+            #[source]
+            SearchLimitTooLarge,
+        ),
+
+        #[error("SearchError: '{0}'")]
+        GetCharactersError(
+            #[from]
+            #[source]
+            GetCharactersError,
+        ),
+
+        #[error("SearchError: '{0}'")]
+        CannotConvertSqlAugmentationToAugmentation(
+            #[from]
+            #[source]
+            CannotConvertSqlAugmentationToAugmentation,
+        ),
+    }
+
+    impl ErrorCode for SearchError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::SearchLimitTooLarge(e) => e.code(),
+                Self::GetCharactersError(e) => e.code(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::SearchLimitTooLarge(e) => e.fields(),
+                Self::GetCharactersError(e) => e.fields(),
+                Self::CannotConvertSqlAugmentationToAugmentation(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for SearchError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+}
@@ -1,12 +1,16 @@
-use error::GetUserByIdError;
+use error::{
+    CannotFindUserById, DisplayNameTaken, GetUserByIdError, GetUsersByIdsError, SetDisplayNameError,
+};
 
 use crate::{
-    error::SqlxErrorConverter,
+    error::{SqlxError, SqlxErrorConverter},
     log_location,
     model::{scalars::id::Id, types::user::User},
     state::State,
 };
 
+use super::ids_to_json_array;
+
 #[derive(sqlx::Type)]
 #[allow(non_snake_case)]
 pub(super) struct SqlUser {
@@ -45,13 +49,101 @@ pub async fn get_user_by_id(state: &State, id: Id) -> Result<Option<User>, GetUs
     })
 }
 
+/// Loads the users with the given ids, ids without a user are left out of the result
+pub async fn get_users_by_ids(state: &State, ids: &[Id]) -> Result<Vec<User>, GetUsersByIdsError> {
+    let ids_json = ids_to_json_array(ids);
+
+    let records = sqlx::query!(
+        "
+            SELECT
+                Id, DisplayName, EmailAddress
+            FROM
+                Users
+            WHERE
+                Id IN (SELECT value FROM json_each(?))
+        ",
+        ids_json,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(records
+        .into_iter()
+        .map(|record| User {
+            state: state.clone(),
+            id: record.Id.into(),
+            nickname: record.DisplayName,
+            email_address: record.EmailAddress,
+        })
+        .collect())
+}
+
+pub async fn set_display_name(
+    state: &State,
+    id: Id,
+    display_name: &str,
+) -> Result<User, SetDisplayNameError> {
+    let id_str = id.as_string_ref();
+
+    let record = sqlx::query_as!(
+        SqlUser,
+        "
+            UPDATE
+                Users
+            SET
+                DisplayName = ?
+            WHERE
+                Id = ?
+            RETURNING
+                DisplayName, EmailAddress
+        ",
+        display_name,
+        id_str,
+    )
+    .fetch_optional(state.database.connection_pool_ref())
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref database_error) if database_error.is_unique_violation() => {
+            SetDisplayNameError::from(DisplayNameTaken(display_name.into()))
+        }
+        e => SqlxError::from(e).into(),
+    })
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?
+    .ok_or_else(|| CannotFindUserById(id.clone()))
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(User {
+        state: state.clone(),
+        id,
+        nickname: record.DisplayName,
+        email_address: record.EmailAddress,
+    })
+}
+
 pub mod error {
-    use crate::{error::SqlxError, model::scalars::id::Id};
+    use async_graphql::{ErrorExtensions, InputType, Value};
+
+    use crate::{
+        error::{ErrorCode, SqlxError},
+        model::scalars::id::Id,
+    };
 
     #[derive(Clone, Debug, thiserror::Error)]
     #[error("CannotFindUserById: user_id = '{0:?}'")]
     pub struct CannotFindUserById(pub Id);
 
+    impl ErrorCode for CannotFindUserById {
+        fn code(&self) -> &'static str {
+            "USER_NOT_FOUND"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![("userId", self.0.to_value())]
+        }
+    }
+
     #[derive(Clone, Debug, thiserror::Error)]
     pub enum GetUserByIdError {
         #[error("GetUserByIdError: '{0}'")]
@@ -61,4 +153,110 @@ pub mod error {
             SqlxError,
         ),
     }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetUsersByIdsError {
+        #[error("GetUsersByIdsError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    #[error("DisplayNameTaken: display_name = '{0}'")]
+    pub struct DisplayNameTaken(pub String);
+
+    impl ErrorCode for DisplayNameTaken {
+        fn code(&self) -> &'static str {
+            "DISPLAY_NAME_TAKEN"
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            vec![("displayName", Value::from(self.0.as_str()))]
+        }
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum SetDisplayNameError {
+        #[error("SetDisplayNameError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("SetDisplayNameError: '{0}'")]
+        CannotFindUserById(
+            #[from]
+            #[source]
+            CannotFindUserById,
+        ),
+
+        #[error("SetDisplayNameError: '{0}'")]
+        DisplayNameTaken(
+            #[from]
+            #[source]
+            DisplayNameTaken,
+        ),
+    }
+
+    impl ErrorCode for GetUserByIdError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetUserByIdError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for SetDisplayNameError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotFindUserById(e) => e.code(),
+                Self::DisplayNameTaken(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotFindUserById(e) => e.fields(),
+                Self::DisplayNameTaken(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorCode for GetUsersByIdsError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetUsersByIdsError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
 }
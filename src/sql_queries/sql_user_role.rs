@@ -0,0 +1,238 @@
+use error::{GetUserRolesError, GrantRoleError, RevokeRoleError};
+
+use crate::{
+    error::{SqlxError, SqlxErrorConverter},
+    log_location,
+    model::{enums::role::Role, scalars::id::Id},
+    state::State,
+};
+
+use super::sql_user::error::CannotFindUserById;
+
+/// Returns the roles of the user, every user is a `Player`, even without a row in `UserRoles`
+pub async fn get_user_roles(state: &State, user_id: &Id) -> Result<Vec<Role>, GetUserRolesError> {
+    let user_id_str = user_id.as_string_ref();
+
+    let records = sqlx::query!(
+        "
+            SELECT
+                Role
+            FROM
+                UserRoles
+            WHERE
+                UserId = ?
+            ORDER BY
+                Role
+        ",
+        user_id_str,
+    )
+    .fetch_all(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    let mut roles: Vec<Role> = records
+        .into_iter()
+        .map(|record| record.Role.parse())
+        .collect::<Result<_, _>>()
+        .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    if !roles.contains(&Role::Player) {
+        roles.push(Role::Player);
+    }
+
+    Ok(roles)
+}
+
+pub async fn grant_role(
+    state: &State,
+    user_id: &Id,
+    role: Role,
+) -> Result<Vec<Role>, GrantRoleError> {
+    let user_id_str = user_id.as_string_ref();
+    let role_str: &str = role.into();
+
+    sqlx::query!(
+        "
+            INSERT OR IGNORE INTO
+                UserRoles (UserId, Role)
+            VALUES
+                (?, ?)
+        ",
+        user_id_str,
+        role_str,
+    )
+    .execute(state.database.connection_pool_ref())
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(ref database_error) if database_error.is_foreign_key_violation() => {
+            GrantRoleError::from(CannotFindUserById(user_id.clone()))
+        }
+        e => SqlxError::from(e).into(),
+    })
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(get_user_roles(state, user_id).await?)
+}
+
+pub async fn revoke_role(
+    state: &State,
+    user_id: &Id,
+    role: Role,
+) -> Result<Vec<Role>, RevokeRoleError> {
+    let user_id_str = user_id.as_string_ref();
+    let role_str: &str = role.into();
+
+    sqlx::query!(
+        "
+            DELETE FROM
+                UserRoles
+            WHERE
+                UserId = ? AND Role = ?
+        ",
+        user_id_str,
+        role_str,
+    )
+    .execute(state.database.connection_pool_ref())
+    .await
+    .to_sqlx_error_result()
+    .inspect_err(|e| log::error!("{}, error = {e}", log_location!()))?;
+
+    Ok(get_user_roles(state, user_id).await?)
+}
+
+pub mod error {
+    use async_graphql::{ErrorExtensions, Value};
+
+    use crate::{
+        error::{ErrorCode, SqlxError},
+        model::enums::role::error::InvalidRole,
+        sql_queries::sql_user::error::CannotFindUserById,
+    };
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GetUserRolesError {
+        #[error("GetUserRolesError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GetUserRolesError: '{0}'")]
+        InvalidRole(
+            #[from]
+            #[source]
+            InvalidRole,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum GrantRoleError {
+        #[error("GrantRoleError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("GrantRoleError: '{0}'")]
+        CannotFindUserById(
+            #[from]
+            #[source]
+            CannotFindUserById,
+        ),
+
+        #[error("GrantRoleError: '{0}'")]
+        GetUserRolesError(
+            #[from]
+            #[source]
+            GetUserRolesError,
+        ),
+    }
+
+    #[derive(Clone, Debug, thiserror::Error)]
+    pub enum RevokeRoleError {
+        #[error("RevokeRoleError: '{0}'")]
+        SqlxError(
+            #[from]
+            #[source]
+            SqlxError,
+        ),
+
+        #[error("RevokeRoleError: '{0}'")]
+        GetUserRolesError(
+            #[from]
+            #[source]
+            GetUserRolesError,
+        ),
+    }
+
+    impl ErrorCode for GetUserRolesError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::InvalidRole(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::InvalidRole(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GetUserRolesError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for GrantRoleError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::CannotFindUserById(e) => e.code(),
+                Self::GetUserRolesError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::CannotFindUserById(e) => e.fields(),
+                Self::GetUserRolesError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for GrantRoleError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+
+    impl ErrorCode for RevokeRoleError {
+        fn code(&self) -> &'static str {
+            match self {
+                Self::SqlxError(e) => e.code(),
+                Self::GetUserRolesError(e) => e.code(),
+            }
+        }
+
+        fn fields(&self) -> Vec<(&'static str, Value)> {
+            match self {
+                Self::SqlxError(e) => e.fields(),
+                Self::GetUserRolesError(e) => e.fields(),
+            }
+        }
+    }
+
+    impl ErrorExtensions for RevokeRoleError {
+        fn extend(&self) -> async_graphql::Error {
+            self.to_graphql_error()
+        }
+    }
+}
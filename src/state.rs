@@ -1,16 +1,20 @@
-use std::path::Path;
-
-use crate::{database::Database, error::DatabaseOpenError};
+use crate::{
+    database::{Database, DatabaseOptions},
+    error::DatabaseOpenError,
+    metrics::Metrics,
+};
 
 #[derive(Clone)]
 pub struct State {
     pub database: Database,
+    pub metrics: Metrics,
 }
 
 impl State {
-    pub async fn new(db_folder_path: impl AsRef<Path>) -> Result<Self, DatabaseOpenError> {
+    pub async fn new(database_options: &DatabaseOptions) -> Result<Self, DatabaseOpenError> {
         Ok(Self {
-            database: Database::open(db_folder_path).await?,
+            database: Database::open(database_options).await?,
+            metrics: Metrics::new(),
         })
     }
 }